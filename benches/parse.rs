@@ -0,0 +1,90 @@
+use std::env;
+use std::fs;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use odin::app::{self, loader};
+
+/// Saved copy of OpenWrt's `supported devices` page to benchmark against
+const BENCH_PAGE_VAR: &str = "ODIN_BENCH_PAGE";
+
+/// Rows in the generated page, used when no saved page is available
+const GENERATED_ROWS: usize = 5000;
+
+/// Builds a page shaped like the ToH, with `rows` devices and some surrounding noise
+fn generate_page(rows: usize) -> String {
+    let mut html_content = String::from(r"
+        <html>
+            <head>
+                <title>Table of Hardware</title>
+                <script>var rows = '<tr><td>not a row</td></tr>';</script>
+            </head>
+            <body>
+                <div class='table dataaggregation'>
+                    <table>
+                        <thead>
+                            <tr><th>#</th><th>Brand</th><th>Model</th><th>Versions</th><th>Supported Current Rel</th><th>Device Page</th><th /></tr>
+                            <tr><th /><th><input name='brand' /></th><th /><th /><th /><th /><th /></tr>
+                        </thead>
+                        <tbody>
+    ");
+
+    for i in 0..rows {
+        html_content.push_str(&format!(r"
+            <tr class='row{0}'>
+                <td class='rownumbers'>{0}</td>
+                <td class='brand'>Brand {1}</td>
+                <td class='model'>Model <span>{0}</span></td>
+                <td class='versions'>v1, v2, v{2}</td>
+                <td class='supported_current_rel'>
+                    <a href='/releases/19.07.{2}' class='wikilink1' title='releases:19.07.{2}'>19.07.{2}</a>
+                </td>
+                <td class='deviceurl'>
+                    <a href='/toh/brand_{1}/model_{0}' class='wikilink1' title='toh:brand_{1}:model_{0}'>toh:brand_{1}:model_{0}</a>
+                </td>
+                <td><a href='/toh/hwdata/brand_{1}/model_{0}?do=edit' class='wikilink1'>Edit</a></td>
+            </tr>
+        ", i, i % 250, i % 5));
+    }
+
+    html_content.push_str(r"
+                        </tbody>
+                    </table>
+                </div>
+            </body>
+        </html>
+    ");
+
+    html_content
+}
+
+/// Loads the page pointed to by `ODIN_BENCH_PAGE` or odin's saved page, else generates one
+fn load_page() -> String {
+    let saved_page = env::var(BENCH_PAGE_VAR)
+        .ok()
+        .or_else(|| Some(shellexpand::tilde(app::ODIN_DEVICE_PAGE).to_string()))
+        .and_then(|file| fs::read_to_string(file).ok());
+
+    saved_page.unwrap_or_else(|| generate_page(GENERATED_ROWS))
+}
+
+fn bench_parsers(c: &mut Criterion) {
+    let html_content = load_page();
+    let mut group = c.benchmark_group("load_manufacturers");
+
+    group.throughput(Throughput::Bytes(html_content.len() as u64));
+    group.sample_size(10);
+
+    group.bench_function("dom", |b| {
+        b.iter(|| loader::load_manufacturers_from_dom(&html_content).unwrap())
+    });
+    group.bench_function("stream", |b| {
+        b.iter(|| loader::load_manufacturers_from(&html_content).unwrap())
+    });
+    group.bench_function("stream_reader", |b| {
+        b.iter(|| loader::load_manufacturers_from_reader(html_content.as_bytes()).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parsers);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+/// Catalog bundled with the `bundled-snapshot` feature, unless `ODIN_SNAPSHOT` points elsewhere
+const DEFAULT_SNAPSHOT: &str = "snapshot/catalog.json";
+
+/// Returns the date of `days` since the unix epoch as YYYY-MM-DD, see
+/// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_date(days: i64) -> String {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Returns the date the snapshot at `path` was taken, `ODIN_SNAPSHOT_DATE` when set,
+/// else the day it was last modified
+fn snapshot_date(path: &Path) -> io::Result<String> {
+    if let Ok(date) = env::var("ODIN_SNAPSHOT_DATE") {
+        return Ok(date);
+    }
+
+    let seconds = fs::metadata(path)?
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+
+    Ok(civil_date(seconds.div_euclid(86_400)))
+}
+
+/// Compresses the snapshot catalog into `OUT_DIR`, for `loader::snapshot` to embed
+///
+/// Without `ODIN_SNAPSHOT` & with no catalog at `DEFAULT_SNAPSHOT`, nothing is embedded so a clean
+/// checkout still builds, `loader::snapshot` then has no snapshot to load
+fn bundle_snapshot() -> io::Result<()> {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR"));
+    let explicit = env::var("ODIN_SNAPSHOT").ok().map(PathBuf::from);
+    let path = explicit.clone().unwrap_or_else(|| manifest_dir.join(DEFAULT_SNAPSHOT));
+    let out = PathBuf::from(env::var("OUT_DIR").expect("cargo sets OUT_DIR")).join("snapshot.json.gz");
+
+    println!("cargo:rerun-if-env-changed=ODIN_SNAPSHOT");
+    println!("cargo:rerun-if-env-changed=ODIN_SNAPSHOT_DATE");
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    if explicit.is_none() && !path.exists() {
+        println!("cargo:warning=No catalog at {}, bundling no snapshot, write one with `odin list -m -a --format json`",
+            DEFAULT_SNAPSHOT);
+        println!("cargo:rustc-env=ODIN_SNAPSHOT_DATE=");
+
+        return File::create(&out).map(|_| ());
+    }
+
+    let catalog = fs::read(&path)
+        .map_err(|err| io::Error::new(err.kind(), format!(
+            "{}: {}, write one with `odin list -m -a --format json` or point ODIN_SNAPSHOT at one",
+            path.display(), err)))?;
+    let mut encoder = GzEncoder::new(File::create(&out)?, Compression::best());
+
+    encoder.write_all(&catalog)?;
+    encoder.finish()?;
+
+    println!("cargo:rustc-env=ODIN_SNAPSHOT_DATE={}", snapshot_date(&path)?);
+
+    Ok(())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var_os("CARGO_FEATURE_BUNDLED_SNAPSHOT").is_none() {
+        return;
+    }
+
+    if let Err(err) = bundle_snapshot() {
+        panic!("Couldn't bundle the catalog snapshot: {}", err);
+    }
+}
@@ -1,21 +1,53 @@
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fs;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
 use colored::*;
+use reqwest::Url;
 use shellexpand;
-use crate::log::Log;
 
+mod compare;
 mod data;
-mod loader;
+mod export;
+mod fleet;
+mod format;
+mod hardware;
+mod identify;
+mod images;
+mod links;
+pub mod loader;
+mod query;
+mod recommend;
+mod releases;
+mod schema;
+mod serve;
+mod template;
+mod tui;
+mod watchlist;
 
-use data::{Model, Manufacturer, Version};
+use data::{Model, Manufacturer, Registry, ResourceClass, Revision, Version, ReleaseNumber};
+pub use data::Resources;
+pub use recommend::Requirements;
+pub use template::Template;
+use watchlist::{Watchlist, FollowedDevice};
+pub use format::Format;
 
-const OPENWRT_ROOT_PAGE: &str = "https://openwrt.org";
 const ALL_DEVICES_PAGE: &str = "https://openwrt.org/toh/start";
+const RELEASES_PAGE: &str = "https://openwrt.org/releases/start";
 const ODIN_HOME: &str = "~/.config/odin";
-const ODIN_DEVICE_PAGE: &str = "~/.config/odin/devices.html";
+pub const ODIN_DEVICE_PAGE: &str = "~/.config/odin/devices.html";
+const ODIN_CACHED_PAGE: &str = "~/.config/odin/cache/devices.html";
+const ODIN_RELEASES_PAGE: &str = "~/.config/odin/releases.html";
+const ODIN_HARDWARE: &str = "~/.config/odin/toh.tsv";
+const ODIN_WATCHLIST: &str = "~/.config/odin/watchlist.json";
+const ODIN_PROFILES: &str = "~/.config/odin/profiles";
 
 pub struct App<'f> {
-    file: Option<&'f str>
+    file: Option<&'f str>,
+    releases: Option<&'f str>,
+    hardware: Option<&'f str>
 }
 
 impl<'f> App<'f> {
@@ -24,74 +56,765 @@ impl<'f> App<'f> {
     }
 
     pub fn new(file: Option<&'f str>) -> Self {
-        let application = Self { file };
+        let application = Self { file, releases: None, hardware: None };
         
         application.init();
 
         application
     }
 
-    /// Lists all brand names
-    pub fn list_brands(&self) -> Result<(), Box<dyn Error>> {
-        let manufacturers = loader::load_manufacturers(self.file)?;
+    /// Loads the release registry from `releases` rather than odin home or the web
+    pub fn with_releases(mut self, releases: Option<&'f str>) -> Self {
+        self.releases = releases;
+
+        self
+    }
+
+    /// Loads technical data of hardware revisions from `hardware` rather than odin home
+    pub fn with_hardware(mut self, hardware: Option<&'f str>) -> Self {
+        self.hardware = hardware;
+
+        self
+    }
+
+    /// Loads all brand details, see `loader::load_manufacturers`, along with technical data of their
+    /// hardware revisions from the ToH dump or saved device pages, when there are any
+    fn load_manufacturers(&self) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+        Ok(self.load_manufacturers_with_source()?.map(|(manufacturers, _)| manufacturers))
+    }
+
+    /// Loads all brand details along with where they were loaded from, see `load_manufacturers`
+    fn load_manufacturers_with_source(&self) -> Result<Option<loader::Sourced>, Box<dyn Error>> {
+        let mut loaded = loader::load_manufacturers_with_source(self.file)?;
+        let path = self.hardware.unwrap_or(ODIN_HARDWARE);
+
+        if self.hardware.is_none() && fs::metadata(shellexpand::tilde(path).trim()).is_err() {
+            return Ok(loaded);
+        }
+
+        let records = hardware::load_records(path)
+            .map_err(|err| format!("{}: {}", path, err))?;
+
+        if let Some((manufacturers, _)) = loaded.as_mut() {
+            let attached = hardware::attach(manufacturers, &records);
+
+            info!("Loaded technical data from: {}", path);
+            debug!("Matched {} of {} record(s) to hardware revisions", attached, records.len());
+        }
+
+        Ok(loaded)
+    }
+
+    /// Loads the release registry when `wanted`, see `releases::load_registry`
+    fn registry(&self, wanted: bool) -> Result<Option<Registry>, Box<dyn Error>> {
+        if !wanted {
+            return Ok(None);
+        }
+
+        releases::load_registry(self.releases).map(Some)
+    }
+
+    /// Prints `manufacturer` as a table, along with lifecycle status of its models when `registry` is given
+    fn print_manufacturer(manufacturer: &Manufacturer, registry: Option<&Registry>) {
+        match registry {
+            Some(registry) => println!("{}", manufacturer.with_status(registry)),
+            None => println!("{}", manufacturer)
+        }
+    }
+
+    /// Prints `manufacturers` as a JSON catalog, which can be loaded back with `-f`
+    fn print_catalog(manufacturers: &[&Manufacturer]) -> Result<(), Box<dyn Error>> {
+        println!("{}", schema::Catalog::new(manufacturers).to_json()?);
+
+        Ok(())
+    }
+
+    /// Lists all brand names, or writes all brands as a JSON catalog with `Format::Json`,
+    /// or renders each brand through `template`, which takes precedence over `format`
+    pub fn list_brands(&self, format: Format, template: Option<&Template>) -> Result<(), Box<dyn Error>> {
+        let manufacturers = match self.load_manufacturers()? {
+            Some(manufacturers) => manufacturers,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+
+        if let Some(template) = template {
+            print!("{}", template.render_brands(&manufacturers.iter().collect::<Vec<&Manufacturer>>())?);
+
+            return Ok(());
+        }
+
+        if format == Format::Json {
+            return Self::print_catalog(&manufacturers.iter().collect::<Vec<&Manufacturer>>());
+        }
     
-        if let Some(manufacturers) = manufacturers {
-            let mut i = 1;
+        info!("Found {} brand(s)!", manufacturers.len());
+
+        for (i, brand) in manufacturers.iter().enumerate() {
+            println!("{}. {} - {} model(s)", i + 1, brand.name().bold(), brand.count());
+        }
+
+        info!("Found {} brand(s)!", manufacturers.len());
     
-            Log::print_ok(format!("Found {} brand(s)!", manufacturers.len()));
-            println!();
+        Ok(())
+    }
     
-            for brand in &manufacturers {
-                println!("{}. {} - {} model(s)", i, brand.name().bold(), brand.count());
-                
-                i += 1;
-            }
-            
-            println!();
-            Log::print_ok(format!("Found {} brand(s)!", manufacturers.len()));
+    /// Keeps only hardware revisions of `manufacturers` with at least `minimum` resources,
+    /// brands left without any models are removed
+    fn retain_resources(manufacturers: &mut Vec<Manufacturer>, minimum: Resources) {
+        if !minimum.is_set() {
+            return;
         }
-        else {
-            Log::print_error(format!("Found 0 brand(s)!"));
+
+        for manufacturer in manufacturers.iter_mut() {
+            manufacturer.retain_revisions(|revision| minimum.are_met_by(revision.hardware()));
+        }
+
+        manufacturers.retain(|manufacturer| manufacturer.count() > 0);
+    }
+
+    /// Warns about listed hardware revisions of `manufacturers` below OpenWrt's 8/64 guidance
+    fn warn_low_resources(manufacturers: &[&Manufacturer]) {
+        let low = manufacturers.iter()
+            .flat_map(|manufacturer| manufacturer.models())
+            .flat_map(|model| model.revisions())
+            .filter_map(|revision| revision.hardware().and_then(|hardware| hardware.resource_class()))
+            .filter(|class| *class < ResourceClass::Sufficient)
+            .count();
+
+        if low > 0 {
+            warn!("{} hardware revision(s) at 4/32 or 8/64, these can't run current releases or soon won't!", low);
+        }
+    }
+
+    /// Lists all models for a given `brand`, annotated with lifecycle status of their release with `status`
+    /// & limited to hardware revisions with at least `minimum` resources, `template` takes precedence over `format`
+    pub fn list_models_for(&self, brand: &str, format: Format, status: bool, minimum: Resources, template: Option<&Template>) -> Result<(), Box<dyn Error>> {
+        let mut manufacturers = self.load_manufacturers()?
+            .unwrap_or_default();
+        let registry = self.registry(status && (format == Format::Table || template.is_some()))?;
+        
+        Self::retain_resources(&mut manufacturers, minimum);
+
+        let manufacturer = match data::find_manufacturer(&manufacturers, brand) {
+            Some(manufacturer) => manufacturer,
+            None => return Err(format!("Found 0 brand(s) for {}!", brand).into())
+        };
+
+        if let Some(template) = template {
+            print!("{}", template.render_models(&[manufacturer], registry.as_ref())?);
+
+            return Ok(());
+        }
+
+        if format == Format::Json {
+            return Self::print_catalog(&[manufacturer]);
         }
+
+        Self::print_manufacturer(manufacturer, registry.as_ref());
+        Self::warn_low_resources(&[manufacturer]);
     
         Ok(())
     }
     
-    /// Lists all models for a given `brand`
-    pub fn list_models_for(&self, brand: &str) -> Result<(), Box<dyn Error>> {
-        let manufacturers = loader::load_manufacturers(self.file)?;
+    /// Lists all models for all available brands, annotated with lifecycle status of their release with `status`
+    /// & limited to hardware revisions with at least `minimum` resources, `template` takes precedence over `format`
+    pub fn list_models_for_all(&self, format: Format, status: bool, minimum: Resources, template: Option<&Template>) -> Result<(), Box<dyn Error>> {
+        let mut manufacturers = self.load_manufacturers()?
+            .unwrap_or_default();
+        let registry = self.registry(status && (format == Format::Table || template.is_some()))?;
+
+        Self::retain_resources(&mut manufacturers, minimum);
+
+        if manufacturers.is_empty() {
+            return Err("Found 0 brand(s)!".into());
+        }
+
+        if let Some(template) = template {
+            print!("{}", template.render_models(&manufacturers.iter().collect::<Vec<&Manufacturer>>(), registry.as_ref())?);
+
+            return Ok(());
+        }
+
+        if format == Format::Json {
+            return Self::print_catalog(&manufacturers.iter().collect::<Vec<&Manufacturer>>());
+        }
         
-        if let Some(manufacturers) = manufacturers {
-            let manufacturer = manufacturers.iter()
-                .find(|manufacturer| brand.to_lowercase() == manufacturer.name().to_lowercase());
+        for manufacturer in &manufacturers {
+            Self::print_manufacturer(manufacturer, registry.as_ref());
+        }
+
+        Self::warn_low_resources(&manufacturers.iter().collect::<Vec<&Manufacturer>>());
+    
+        Ok(())
+    }
+
+    /// Prints a data quality report on the devices page, along with a summary per problem
+    /// Fails when the page has structural problems, like rows of an unexpected shape
+    pub fn lint(&self) -> Result<(), Box<dyn Error>> {
+        let parsed = match loader::parse_manufacturers(self.file)? {
+            Some(parsed) => parsed,
+            None => return Err("Error loading the devices page!".into())
+        };
+
+        if parsed.rows == 0 {
+            return Err("Found 0 row(s), the devices table is missing or empty!".into());
+        }
 
-            if let Some(manufacturer) = manufacturer {
-                println!("{}", manufacturer);
+        let mut summary: BTreeMap<String, usize> = BTreeMap::new();
+        let structural = parsed.warnings
+            .iter()
+            .filter(|warning| warning.problem.is_structural())
+            .count();
+
+        for warning in &parsed.warnings {
+            if warning.problem.is_structural() {
+                println!("{}", warning.to_string().red());
             }
             else {
-                Log::print_error(format!("Found 0 brand(s)!"));
+                println!("{}", warning.to_string().yellow());
             }
+
+            *summary.entry(warning.problem.to_string()).or_default() += 1;
         }
-        else {
-            Log::print_error(format!("Found 0 brand(s)!"));
+
+        if !parsed.warnings.is_empty() {
+            println!();
         }
-    
+
+        for (problem, count) in &summary {
+            println!("{:>6} row(s) {}", count, problem);
+        }
+
+        if !summary.is_empty() {
+            println!();
+        }
+
+        let brands = parsed.manufacturers
+            .map(|manufacturers| manufacturers.len())
+            .unwrap_or_default();
+
+        info!("Checked {} row(s) of {} brand(s), found {} problem(s)!", parsed.rows, brands, parsed.warnings.len());
+
+        if structural > 0 {
+            return Err(format!("Found {} structural problem(s), the page layout may have changed!", structural).into());
+        }
+
         Ok(())
     }
-    
-    /// Lists all models for all available brands
-    pub fn list_models_for_all(&self) -> Result<(), Box<dyn Error>> {
-        let manufacturers = loader::load_manufacturers(self.file)?;
-        
-        if let Some(manufacturers) = manufacturers {
+
+    /// Checks device pages of all models, on `mirror` when given, & reports dead ones
+    /// Fails when any device page is dead
+    pub fn check_links(&self, mirror: Option<&str>, jobs: usize, timeout: u64) -> Result<(), Box<dyn Error>> {
+        let mirror = mirror.map(Url::parse)
+            .transpose()
+            .map_err(|err| format!("Invalid mirror URL: {}", err))?;
+        let manufacturers = match self.load_manufacturers()? {
+            Some(manufacturers) => manufacturers,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+        let links = links::check(links::device_pages(&manufacturers), mirror.as_ref(), jobs, Duration::from_secs(timeout))?;
+        let mut dead = 0;
+        let mut unreachable = 0;
+
+        for link in &links {
+            let status = match &link.status {
+                links::LinkStatus::Alive(_) => continue,
+                links::LinkStatus::Dead(status) => {
+                    dead += 1;
+
+                    format!("Dead ({})", status).red()
+                },
+                links::LinkStatus::Unreachable(err) => {
+                    unreachable += 1;
+
+                    format!("Unreachable ({})", err).yellow()
+                }
+            };
+
+            println!("{} {}", status.bold(), link.checked_url);
+
+            if link.checked_url != link.url {
+                println!("  {}", link.url);
+            }
+
+            println!("  {}", link.models.join(", "));
+        }
+
+        if dead + unreachable > 0 {
+            println!();
+        }
+
+        info!("Checked {} device page(s), {} dead & {} unreachable!", links.len(), dead, unreachable);
+
+        if dead > 0 {
+            return Err(format!("Found {} dead device page(s)!", dead).into());
+        }
+
+        Ok(())
+    }
+
+    /// Reports how devices in `inventory` csv stand against the catalog, in given `format`
+    pub fn fleet_report(&self, inventory: &str, format: Format) -> Result<(), Box<dyn Error>> {
+        let devices = fleet::load_inventory(inventory)?;
+        let manufacturers = match self.load_manufacturers()? {
+            Some(manufacturers) => manufacturers,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+        let entries = fleet::report(&manufacturers, devices);
+
+        match format {
+            Format::Table => fleet::print_table(&entries),
+            Format::Csv => fleet::print_csv(&entries)?,
+            Format::Json => fleet::print_json(&entries)?
+        }
+
+        Ok(())
+    }
+
+    /// Compares models of `specs`, each `brand/model` or `brand/model@version`, side by side
+    pub fn compare(&self, specs: &[&str], format: Format) -> Result<(), Box<dyn Error>> {
+        let manufacturers = match self.load_manufacturers()? {
+            Some(manufacturers) => manufacturers,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+        let candidates = specs.iter()
+            .map(|spec| compare::resolve(&manufacturers, spec))
+            .collect::<Result<Vec<compare::Candidate>, String>>()?;
+        let comparison = compare::compare(&candidates);
+
+        match format {
+            Format::Json => compare::print_json(&comparison)?,
+            _ => compare::print_table(&comparison)
+        }
+
+        Ok(())
+    }
+
+    /// Recommends up to `limit` hardware revisions meeting `requirements`, along with the ones in
+    /// `file` when given, flags taking precedence, best first with why each qualified
+    pub fn recommend(&self, file: Option<&str>, requirements: Requirements, limit: usize, format: Format) -> Result<(), Box<dyn Error>> {
+        let requirements = match file {
+            Some(file) => Requirements::load(file)?.merge(requirements),
+            None => requirements
+        };
+        let manufacturers = match self.load_manufacturers()? {
+            Some(manufacturers) => manufacturers,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+
+        if requirements.needs_hardware() && !manufacturers.iter().any(|manufacturer| manufacturer.has_hardware()) {
+            warn!("No technical data loaded, hardware requirements can't be met, see --hardware!");
+        }
+
+        let mut recommendations = recommend::recommend(&manufacturers, &requirements)?;
+        let found = recommendations.len();
+
+        recommendations.truncate(limit);
+
+        match format {
+            Format::Json => recommend::print_json(&recommendations)?,
+            _ => recommend::print_table(&recommendations)
+        }
+
+        if found == 0 {
+            return Err("Found 0 model(s) meeting the requirements!".into());
+        }
+
+        info!("Found {} hardware revision(s) meeting the requirements, showing {}!", found, recommendations.len());
+
+        Ok(())
+    }
+
+    /// Lists hardware revisions matching `expression`, all of them without one, see `query::Query`
+    ///
+    /// With `group_by`, they're grouped by their value of that field, with `count`, only
+    /// counted, in total or per group
+    pub fn query(&self, expression: Option<&str>, group_by: Option<&str>, count: bool, format: Format) -> Result<(), Box<dyn Error>> {
+        let query = expression.map(str::parse::<query::Query>).transpose()?;
+        let group_by = group_by.map(str::parse::<query::Field>).transpose()?;
+        let mut manufacturers = match self.load_manufacturers()? {
+            Some(manufacturers) => manufacturers,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+
+        if let Some(query) = query {
+            if query.is_technical() && !manufacturers.iter().any(|manufacturer| manufacturer.has_hardware()) {
+                warn!("No technical data loaded, comparisons on it won't hold, see --hardware!");
+            }
+
+            query::filter(&mut manufacturers, &query);
+        }
+
+        let found = manufacturers.iter()
+            .flat_map(|manufacturer| manufacturer.models())
+            .map(|model| model.revisions().len())
+            .sum::<usize>();
+
+        if let Some(field) = group_by {
+            let groups = query::group(&manufacturers, field, count);
+
+            match format {
+                Format::Json => query::print_json(&groups)?,
+                _ => query::print_table(&groups)
+            }
+        }
+        else if count {
+            match format {
+                Format::Json => println!("{}", serde_json::json!({ "count": found })),
+                _ => println!("{}", found)
+            }
+        }
+        else if format == Format::Json {
+            Self::print_catalog(&manufacturers.iter().collect::<Vec<&Manufacturer>>())?;
+        }
+        else {
             for manufacturer in &manufacturers {
-                println!("{}", manufacturer);
+                Self::print_manufacturer(manufacturer, None);
             }
         }
+
+        if found == 0 {
+            return Err("Found 0 hardware revision(s) matching the query!".into());
+        }
+
+        info!("Found {} hardware revision(s) matching the query!", found);
+
+        Ok(())
+    }
+
+    /// Exports the catalog into SQLite database at `path`, recording it as a snapshot
+    /// unless it's unchanged since a previous export
+    pub fn export_sqlite(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let (manufacturers, source) = match self.load_manufacturers_with_source()? {
+            Some(loaded) => loaded,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+        let export = export::export_sqlite(&manufacturers, &source, path)?;
+
+        if export.is_new {
+            info!("Exported {} brand(s) & {} model(s) to {} as snapshot {}!",
+                export.brands, export.models, path, export.snapshot_id);
+        }
         else {
-            Log::print_error(format!("Found 0 brand(s)!"));
+            info!("No change(s) since snapshot {} in {}!", export.snapshot_id, path);
         }
-    
+
+        Ok(())
+    }
+
+    /// Follows `model` of `brand`, optionally a specific hardware `version` of it
+    pub fn follow(&self, brand: &str, model: &str, version: Option<&str>) -> Result<(), Box<dyn Error>> {
+        let manufacturers = self.load_manufacturers()?
+            .unwrap_or_default();
+        let found = data::find_manufacturer(&manufacturers, brand)
+            .and_then(|manufacturer| manufacturer.find_revision(model, version))
+            .is_some();
+        let device = FollowedDevice::new(brand, model, version);
+
+        if !found {
+            return Err(format!("Found 0 model(s) for {}!", device.describe()).into());
+        }
+
+        let mut watchlist = Watchlist::load(ODIN_WATCHLIST)?;
+
+        if watchlist.follow(device.clone(), &manufacturers) {
+            watchlist.save(ODIN_WATCHLIST)?;
+            info!("Following {}!", device.describe());
+        }
+        else {
+            warn!("Already following {}!", device.describe());
+        }
+
+        Ok(())
+    }
+
+    /// Stops following `model` of `brand`, or a specific hardware `version` of it
+    pub fn unfollow(&self, brand: &str, model: &str, version: Option<&str>) -> Result<(), Box<dyn Error>> {
+        let device = FollowedDevice::new(brand, model, version);
+        let mut watchlist = Watchlist::load(ODIN_WATCHLIST)?;
+
+        if !watchlist.unfollow(&device) {
+            return Err(format!("Not following {}!", device.describe()).into());
+        }
+
+        watchlist.save(ODIN_WATCHLIST)?;
+        info!("Stopped following {}!", device.describe());
+
+        Ok(())
+    }
+
+    /// Lists followed devices, highlighting ones whose supported release changed since last time
+    /// When something changed, `exec` is run with a summary of changes in `ODIN_CHANGES`
+    ///
+    /// Releases are only saved as seen once `exec` succeeded, so a failed `exec` fails the command
+    /// & the changes are reported again next time
+    pub fn status(&self, exec: Option<&str>) -> Result<(), Box<dyn Error>> {
+        let mut watchlist = Watchlist::load(ODIN_WATCHLIST)?;
+
+        if watchlist.devices().is_empty() {
+            warn!("Not following any device(s)!");
+
+            return Ok(());
+        }
+
+        let (manufacturers, source) = match self.load_manufacturers_with_source()? {
+            Some(sourced) => sourced,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+        let changes = watchlist.update(&manufacturers);
+        let release = |release: &Option<String>| release.clone()
+            .unwrap_or_else(|| "N/A".to_string());
+        let mut summary = Vec::new();
+
+        for (i, change) in changes.iter().enumerate() {
+            if change.is_changed() {
+                let line = format!("{} - {} -> {}", change.device.describe(), release(&change.previous), release(&change.current));
+
+                println!("{}. {}", i + 1, line.bold().yellow());
+                summary.push(line);
+            }
+            else {
+                println!("{}. {} - {}", i + 1, change.device.describe(), release(&change.current));
+            }
+        }
+
+        println!();
+
+        if loader::is_fallback(&source) {
+            return Err(format!("Loaded from {}, which may be out of date, change(s) aren't acted on or saved as seen", source).into());
+        }
+
+        if summary.is_empty() {
+            info!("No change(s) since last status!");
+
+            return watchlist.save(ODIN_WATCHLIST);
+        }
+
+        info!("Found {} change(s) since last status!", summary.len());
+
+        if let Some(exec) = exec {
+            let status = Command::new("sh")
+                .arg("-c")
+                .arg(exec)
+                .env("ODIN_CHANGES", summary.join("\n"))
+                .status()?;
+
+            if !status.success() {
+                return Err(format!("`{}` exited with {}, change(s) will be reported again next time", exec, status).into());
+            }
+        }
+
+        watchlist.save(ODIN_WATCHLIST)
+    }
+
+    /// Identifies the router described by `ubus call system board` output in `board` file,
+    /// or stdin when `board` is `-`, & reports whether a newer supported release exists
+    pub fn identify(&self, board: &str) -> Result<(), Box<dyn Error>> {
+        let board = identify::load_board(board)?;
+        let manufacturers = self.load_manufacturers()?
+            .unwrap_or_default();
+        let identification = match identify::identify(&manufacturers, &board) {
+            Some(identification) => identification,
+            None => return Err(format!("Found 0 model(s) for {}!", board.model()).into())
+        };
+        let model = identification.model;
+        let revision = identification.revision;
+        let not_available = |val: &str| if val.is_empty() { "N/A".to_string() } else { val.to_string() };
+
+        println!("{} {}", "Brand:".bold().bright_cyan(), identification.manufacturer.name());
+        println!("{} {}", "Model:".bold().bright_cyan(), model.name());
+        println!("{} {}", "Version:".bold().bright_cyan(), not_available(identification.version.as_deref().unwrap_or_default()));
+        println!("{} {}", "Target:".bold().bright_cyan(), not_available(board.target()));
+        println!("{} {}", "Installed Release:".bold().bright_cyan(), not_available(board.release()));
+        println!("{} {}", "Supported Release:".bold().bright_cyan(), not_available(revision.openwrt_version().version()));
+        println!("{} {}", "Device Page:".bold().bright_cyan(), not_available(revision.device_page()));
+        println!();
+
+        if identification.version.is_none() && !model.versions_text().is_empty() {
+            warn!("Hardware version not found, showing the latest release for this model!");
+        }
+
+        match (board.release().parse::<ReleaseNumber>(), revision.openwrt_version().release()) {
+            (_, None) => warn!("Found 0 supported release(s)!"),
+            (Err(_), Some(_)) => warn!("Can't compare installed release {}!", not_available(board.release())),
+            (Ok(installed), Some(supported)) if installed < supported => {
+                // the verdict is what was asked for, so it goes to stdout
+                println!("{}", format!("Upgrade available: {} -> {}", installed, supported).green());
+                println!("{}", format!("Release notes: {}", revision.openwrt_version().link()).green());
+            },
+            (Ok(_), Some(_)) => println!("{}", "Already on the latest supported release!".green())
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Lists firmware images of `model` of `brand` for its supported release, from `profiles.json`
+    /// files at `profiles` or in odin home
+    pub fn images(&self, brand: &str, model: &str, version: Option<&str>, profiles: Option<Vec<&str>>, format: Format) -> Result<(), Box<dyn Error>> {
+        let profiles = images::load_profiles(&profiles.unwrap_or_else(|| vec![ODIN_PROFILES]))?;
+        let manufacturers = self.load_manufacturers()?
+            .unwrap_or_default();
+        let manufacturer = data::find_manufacturer(&manufacturers, brand);
+        let found = manufacturer
+            .and_then(|manufacturer| manufacturer.find_revision(model, version).map(|found| (manufacturer, found)));
+        let (manufacturer, model) = match found {
+            Some((manufacturer, (model, _))) => (manufacturer, model),
+            None => return Err(format!("Found 0 model(s) for {} {}!", brand, model).into())
+        };
+        let images = images::find_images(&profiles, manufacturer.name(), model, version);
+        let releases = model.revisions()
+            .iter()
+            .filter(|revision| version.is_none_or(|version| revision.has_version(version)))
+            .map(|revision| revision.openwrt_version().version().to_string())
+            .filter(|release| !release.is_empty())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        if images.is_empty() {
+            return Err(format!("Found 0 image(s) for {} {} on release(s) {}!",
+                manufacturer.name(), model.name(), releases).into());
+        }
+
+        match format {
+            Format::Table => {
+                println!("{} {}", "Brand:".bold().bright_cyan(), manufacturer.name());
+                println!("{} {}", "Model:".bold().bright_cyan(), model.name());
+                println!("{} {}", "Supported Release(s):".bold().bright_cyan(), releases);
+
+                images::print_table(&images);
+                info!("Found {} image(s)!", images.len());
+            },
+            Format::Csv => images::print_csv(&images)?,
+            Format::Json => images::print_json(&images)?
+        }
+
+        Ok(())
+    }
+
+    /// Serves the catalog as JSON on localhost `port`, refreshing it every `refresh` seconds, never with `0`
+    pub fn serve(&self, port: u16, refresh: u64) -> Result<(), Box<dyn Error>> {
+        let manufacturers = match self.load_manufacturers()? {
+            Some(manufacturers) => manufacturers,
+            None => return Err("Found 0 brand(s)!".into())
+        };
+        let (file, releases, hardware) = (self.file.map(String::from), self.releases.map(String::from), self.hardware.map(String::from));
+        let load = move || App::new(file.as_deref())
+            .with_releases(releases.as_deref())
+            .with_hardware(hardware.as_deref())
+            .load_manufacturers();
+        let refresh = Some(refresh)
+            .filter(|refresh| *refresh > 0)
+            .map(Duration::from_secs);
+        let addr = serve::spawn(manufacturers, load, port, refresh)?;
+
+        info!("Serving on http://{}", addr);
+
+        loop {
+            thread::park();
+        }
+    }
+
+    /// Lists all release branches, newest first, with their latest point release, its date & status
+    pub fn releases(&self, format: Format) -> Result<(), Box<dyn Error>> {
+        let registry = releases::load_registry(self.releases)?;
+        let branches = registry.branches();
+
+        if format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(&branches)?);
+
+            return Ok(());
+        }
+
+        println!("{:<10}{:<16}{:<14}{}",
+            "Branch".bold().bright_cyan(),
+            "Latest".bold().bright_cyan(),
+            "Date".bold().bright_cyan(),
+            "Status".bold().bright_cyan());
+
+        for branch in &branches {
+            let date = if branch.latest.date().is_empty() { "N/A" } else { branch.latest.date() };
+
+            println!("{}", branch.status.paint(&format!("{:<10}{:<16}{:<14}{}",
+                branch.branch, branch.latest.version(), date, branch.status)));
+        }
+
+        info!("Found {} release branch(es)!", branches.len());
+
+        Ok(())
+    }
+
+    /// Prints the JSON Schema document of the catalog written by `list --format json`
+    pub fn schema(&self) -> Result<(), Box<dyn Error>> {
+        println!("{}", serde_json::to_string_pretty(&schema::document())?);
+
+        Ok(())
+    }
+
+    /// Browses the catalog in a full screen terminal UI
+    pub fn tui(&self) -> Result<(), Box<dyn Error>> {
+        match self.load_manufacturers()? {
+            Some(manufacturers) => tui::browse(&manufacturers),
+            None => Err("Found 0 brand(s)!".into())
+        }
+    }
+
+    /// Prints names for shell completions, one per line, from the cached catalog only,
+    /// see `completion_names`
+    pub fn complete(&self, kind: &str, values: &[&str]) -> Result<(), Box<dyn Error>> {
+        let manufacturers = loader::load_manufacturers_offline(self.file)?
+            .unwrap_or_default();
+
+        for name in completion_names(&manufacturers, kind, values) {
+            println!("{}", name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns names of `manufacturers` for shell completions, sorted regardless of case
+///
+/// `kind` is either `brands`, completing `values[0]`, or `models`, completing `values[1]`
+/// for brand `values[0]`
+fn completion_names<'m>(manufacturers: &'m [Manufacturer], kind: &str, values: &[&str]) -> Vec<&'m str> {
+    let mut names = match kind {
+        "brands" => manufacturers.iter()
+            .map(|manufacturer| manufacturer.name())
+            .collect::<Vec<&str>>(),
+        _ => values.first()
+            .and_then(|brand| data::find_manufacturer(manufacturers, brand))
+            .map(|manufacturer| manufacturer.models()
+                .iter()
+                .map(|model| model.name().as_str())
+                .collect())
+            .unwrap_or_default()
+    };
+    let prefix = match kind {
+        "brands" => values.first(),
+        _ => values.get(1)
+    };
+    let prefix = data::normalize(prefix.copied().unwrap_or_default());
+
+    names.retain(|name| !name.trim().is_empty() && data::normalize(name).starts_with(&prefix));
+    names.sort_by_key(|name| name.to_lowercase());
+    names.dedup();
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::fixtures::manufacturers;
+
+    #[test]
+    fn completes_brand_and_model_names() {
+        let manufacturers = manufacturers();
+
+        assert_eq!(vec!["Netgear", "TP-Link"], completion_names(&manufacturers, "brands", &[]));
+        assert_eq!(vec!["TP-Link"], completion_names(&manufacturers, "brands", &["tp"]));
+        assert_eq!(vec!["Archer C7", "Archer C7 AC1750"], completion_names(&manufacturers, "models", &["tp-link", "archer  c"]));
+        assert_eq!(vec!["TL-WR703N", "TL-WR841N"], completion_names(&manufacturers, "models", &["TP-LINK", "tl-"]));
+        assert!(completion_names(&manufacturers, "models", &["linksys"]).is_empty());
+    }
+}
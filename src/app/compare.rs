@@ -0,0 +1,199 @@
+use std::error::Error;
+use colored::*;
+use serde::Serialize;
+
+use super::data::{self, Manufacturer, Model, Revision};
+
+/// A model picked for comparison, with the hardware revision it's compared by
+pub type Candidate<'m> = (&'m Manufacturer, &'m Model, &'m Revision);
+
+/// Finds the model `spec` stands for in `manufacturers`, written as `brand/model`
+/// or `brand/model@version` for a given hardware version
+///
+/// Brands are matched by the longest prefix, so model names with `/` in them,
+/// ex: `netgear/wndr3700/wndr3800`, work. Without a version, the revision with the
+/// latest supported release is picked.
+pub fn resolve<'m>(manufacturers: &'m [Manufacturer], spec: &str) -> Result<Candidate<'m>, String> {
+    let (name, version) = match spec.rsplit_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (spec, None)
+    };
+
+    let (manufacturer, model) = name.match_indices('/')
+        .rev()
+        .find_map(|(index, _)| data::find_manufacturer(manufacturers, &name[..index])
+            .map(|manufacturer| (manufacturer, &name[index + 1..])))
+        .ok_or_else(|| format!("Found 0 brand(s) for {}, expected brand/model!", spec))?;
+
+    manufacturer.find_revision(model, version)
+        .map(|(model, revision)| (manufacturer, model, revision))
+        .ok_or_else(|| format!("Found 0 model(s) for {}!", spec))
+}
+
+/// # `Field` represents a compared field, with the value of each model in order
+///
+/// `name` - name of the field, ex: supported_release
+/// `values` - value of each model, `None` when unknown
+/// `differs` - whether models have different values
+#[derive(Serialize, Debug)]
+pub struct Field {
+    name: &'static str,
+    #[serde(skip)]
+    label: &'static str,
+    values: Vec<Option<String>>,
+    differs: bool
+}
+
+/// # `Comparison` represents models side by side, field by field
+///
+/// `models` - compared models, as brand & model name, along with hardware versions for models with several revisions
+/// `fields` - compared fields, technical ones only when known for any model
+#[derive(Serialize, Debug)]
+pub struct Comparison {
+    models: Vec<String>,
+    fields: Vec<Field>
+}
+
+/// Returns `text`, `None` when empty
+fn known(text: String) -> Option<String> {
+    Some(text).filter(|text| !text.is_empty())
+}
+
+/// Compares `candidates` side by side
+pub fn compare(candidates: &[Candidate]) -> Comparison {
+    // revisions are told apart by hardware versions, when a model has several
+    let models = candidates.iter()
+        .map(|(manufacturer, model, revision)| {
+            if model.revisions().len() > 1 && !revision.versions_text().is_empty() {
+                format!("{} {} {}", manufacturer.name(), model.name(), revision.versions_text())
+            }
+            else {
+                format!("{} {}", manufacturer.name(), model.name())
+            }
+        })
+        .collect();
+
+    let field = |name: &'static str, label: &'static str, value: &dyn Fn(&Revision) -> Option<String>| {
+        let values = candidates.iter()
+            .map(|(_, _, revision)| value(revision))
+            .collect::<Vec<Option<String>>>();
+        let differs = values.windows(2).any(|pair| pair[0] != pair[1]);
+
+        Field { name, label, values, differs }
+    };
+
+    let mut fields = vec![
+        field("hardware_versions", "Hardware Versions", &|revision| known(revision.versions_text())),
+        field("supported_release", "Supported Release", &|revision| known(revision.openwrt_version().version().clone())),
+        field("device_page", "Device Page", &|revision| known(revision.device_page().clone()))
+    ];
+
+    if candidates.iter().any(|(_, _, revision)| revision.hardware().is_some()) {
+        fields.extend(vec![
+            field("soc", "SoC", &|revision| revision.hardware().and_then(|hardware| known(hardware.soc.clone()))),
+            field("flash_mb", "Flash MB", &|revision| revision.hardware().and_then(|hardware| hardware.flash_mb).map(|size| size.to_string())),
+            field("ram_mb", "RAM MB", &|revision| revision.hardware().and_then(|hardware| hardware.ram_mb).map(|size| size.to_string())),
+            field("radios", "Radios", &|revision| revision.hardware().and_then(|hardware| known(hardware.radios()))),
+            field("ethernet_ports", "Ethernet Ports", &|revision| revision.hardware().and_then(|hardware| hardware.ethernet_ports()).map(|ports| ports.to_string()))
+        ]);
+    }
+
+    Comparison { models, fields }
+}
+
+/// Prints `comparison` as a table, a column per model, with values of differing fields highlighted
+pub fn print_table(comparison: &Comparison) {
+    let label_width = comparison.fields.iter()
+        .map(|field| field.label.len())
+        .max()
+        .unwrap_or_default();
+    let widths = comparison.models.iter()
+        .enumerate()
+        .map(|(i, model)| comparison.fields.iter()
+            .map(|field| field.values[i].as_deref().unwrap_or("N/A").chars().count())
+            .chain(std::iter::once(model.chars().count()))
+            .max()
+            .unwrap_or_default())
+        .collect::<Vec<usize>>();
+
+    print!("{0:1$}  ", "", label_width);
+    for (model, width) in comparison.models.iter().zip(&widths) {
+        print!("{0:1$}  ", model.bold().bright_cyan(), *width);
+    }
+    println!();
+
+    for field in &comparison.fields {
+        print!("{0:1$}  ", field.label.bold().bright_cyan(), label_width);
+
+        for (value, width) in field.values.iter().zip(&widths) {
+            let value = value.as_deref().unwrap_or("N/A");
+
+            if field.differs {
+                print!("{0:1$}  ", value.bold().yellow(), *width);
+            }
+            else {
+                print!("{0:1$}  ", value, *width);
+            }
+        }
+        println!();
+    }
+
+    let differing = comparison.fields.iter()
+        .filter(|field| field.differs)
+        .count();
+
+    println!();
+    println!("{} model(s), {} of {} field(s) differ", comparison.models.len(), differing, comparison.fields.len());
+}
+
+/// Prints `comparison` as pretty printed JSON
+pub fn print_json(comparison: &Comparison) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(comparison)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::manufacturers;
+
+    #[test]
+    fn resolves_brand_model_specs() {
+        let manufacturers = manufacturers();
+
+        let (manufacturer, model, revision) = resolve(&manufacturers, "tp-link/archer c7@v1").unwrap();
+        assert_eq!(("TP-Link", "Archer C7", "17.01.7"), (manufacturer.name(), model.name().as_str(), revision.openwrt_version().version().as_str()));
+
+        let (_, model, _) = resolve(&manufacturers, "netgear/wndr3700/wndr3800").unwrap();
+        assert_eq!("WNDR3700/WNDR3800", model.name());
+
+        assert_eq!("Found 0 model(s) for tp-link/archer c9!", resolve(&manufacturers, "tp-link/archer c9").unwrap_err());
+        assert_eq!("Found 0 brand(s) for archer c7, expected brand/model!", resolve(&manufacturers, "archer c7").unwrap_err());
+    }
+
+    #[test]
+    fn flags_differing_fields() {
+        let manufacturers = manufacturers();
+        let candidates = ["tp-link/archer c7@v2", "netgear/r7800"].iter()
+            .map(|spec| resolve(&manufacturers, spec).unwrap())
+            .collect::<Vec<Candidate>>();
+        let comparison = compare(&candidates);
+
+        let differs = comparison.fields.iter()
+            .map(|field| (field.name, field.differs))
+            .collect::<Vec<(&str, bool)>>();
+
+        assert_eq!(vec![
+            ("hardware_versions", true),
+            ("supported_release", false),
+            ("device_page", true),
+            ("soc", false),
+            ("flash_mb", true),
+            ("ram_mb", true),
+            ("radios", false),
+            ("ethernet_ports", false)
+        ], differs);
+        assert_eq!(vec![Some("16".to_string()), Some("128".to_string())], comparison.fields[4].values);
+    }
+}
@@ -1,7 +1,39 @@
+mod hardware;
+mod hardware_version;
 mod model;
 mod manufacturer;
 mod version;
+mod release_number;
+mod revision;
+mod release;
 
+#[cfg(test)]
+pub mod fixtures;
+
+pub use hardware::{Hardware, ResourceClass, Resources};
+pub use hardware_version::{normalize_version, parse_versions};
 pub use model::Model;
 pub use manufacturer::Manufacturer;
-pub use version::Version;
\ No newline at end of file
+pub use version::Version;
+pub use release_number::ReleaseNumber;
+pub use revision::Revision;
+pub use release::{Registry, Release, Status};
+
+/// Normalizes a brand, model or version name for lookups,
+/// so that they match regardless of case & spacing
+///
+/// ## Example
+///
+/// " TP-Link  Archer C7 " -> "tp-link archer c7"
+pub fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Finds the manufacturer that goes by `brand` in `manufacturers`
+pub fn find_manufacturer<'m>(manufacturers: &'m [Manufacturer], brand: &str) -> Option<&'m Manufacturer> {
+    manufacturers.iter()
+        .find(|manufacturer| manufacturer.is_named(brand))
+}
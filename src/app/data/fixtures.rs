@@ -0,0 +1,84 @@
+//! Catalog data shared by tests
+
+use super::{Hardware, Manufacturer, Model, Revision, Version};
+
+/// Returns a hardware revision with `versions`, supported up to `release` & linked to its release
+/// page, with no device page
+pub fn revision(versions: &[&str], release: &str) -> Revision {
+    revision_with_page(versions, release, "")
+}
+
+/// Same as `revision`, with `device_page`
+pub fn revision_with_page(versions: &[&str], release: &str, device_page: &str) -> Revision {
+    let link = if release.is_empty() { String::new() } else { format!("https://openwrt.org/releases/{}", release) };
+
+    Revision::new(
+        versions.iter().map(|version| version.to_string()).collect(),
+        Version::new(release.to_string(), link),
+        device_page.to_string()
+    )
+}
+
+pub fn model(name: &str, revisions: Vec<Revision>) -> Model {
+    Model::new(name.to_string(), revisions)
+}
+
+/// Returns technical data with `flash_mb` of flash, `ram_mb` of RAM, `wlan_50ghz` 5 GHz Wi-Fi
+/// & `ethernet_1g_ports` gigabit ports
+pub fn hardware(flash_mb: u32, ram_mb: u32, wlan_50ghz: &str, ethernet_1g_ports: u32) -> Hardware {
+    Hardware {
+        flash_mb: Some(flash_mb),
+        ram_mb: Some(ram_mb),
+        wlan_50ghz: wlan_50ghz.to_string(),
+        ethernet_1g_ports: Some(ethernet_1g_ports),
+        ..Hardware::default()
+    }
+}
+
+/// Returns `revision` with technical data `hardware`
+fn with_hardware(mut revision: Revision, hardware: Hardware) -> Revision {
+    revision.set_hardware(hardware);
+
+    revision
+}
+
+/// Returns a small catalog, sorted by name as loaded ones are
+///
+/// Netgear
+///     R7800 - 19.07.3, 128/512
+///     WNDR3700/WNDR3800 - v1 19.07.3
+/// TP-Link
+///     Archer C7 - v1 17.01.7, 8/128; v2, v3 19.07.3, 16/128; v5 19.07.3
+///     Archer C7 AC1750 - v4 18.06.8
+///     TL-WR703N - no supported release
+///     TL-WR841N - v9 18.06.8, 4/32
+pub fn manufacturers() -> Vec<Manufacturer> {
+    manufacturers_on("19.07.3")
+}
+
+/// Same as `manufacturers`, with Archer C7 v2 & v3 supported up to `release`, to tell catalogs
+/// before & after a release apart
+pub fn manufacturers_on(release: &str) -> Vec<Manufacturer> {
+    let page = |path: &str| format!("https://openwrt.org/toh/{}", path);
+
+    vec![
+        Manufacturer::new("Netgear".to_string(), vec![
+            model("R7800", vec![
+                with_hardware(revision_with_page(&[], "19.07.3", &page("netgear/r7800")), hardware(128, 512, "a/n/ac", 5))
+            ]),
+            model("WNDR3700/WNDR3800", vec![revision_with_page(&["v1"], "19.07.3", &page("netgear/wndr3700"))])
+        ]),
+        Manufacturer::new("TP-Link".to_string(), vec![
+            model("Archer C7", vec![
+                with_hardware(revision_with_page(&["v1"], "17.01.7", &page("tp-link/archer_c7_v1")), hardware(8, 128, "a/n", 5)),
+                with_hardware(revision_with_page(&["v2", "v3"], release, &page("tp-link/archer_c7")), hardware(16, 128, "a/n/ac", 5)),
+                revision_with_page(&["v5"], "19.07.3", &page("tp-link/archer_c7"))
+            ]),
+            model("Archer C7 AC1750", vec![revision_with_page(&["v4"], "18.06.8", &page("tp-link/archer_c7"))]),
+            model("TL-WR703N", vec![revision_with_page(&[], "", &page("tp-link/tl-wr703n"))]),
+            model("TL-WR841N", vec![
+                with_hardware(revision_with_page(&["v9"], "18.06.8", &page("tp-link/tl-wr841nd")), hardware(4, 32, "", 5))
+            ])
+        ])
+    ]
+}
@@ -0,0 +1,164 @@
+use std::fmt::{self, Display};
+use serde::{Serialize, Deserialize};
+
+/// # `ResourceClass` represents how a device stands against OpenWrt's low resource guidance
+///
+/// `FourThirtyTwo` - 4 MB flash or 32 MB RAM at most, can't run current releases anymore
+/// `EightSixtyFour` - 8 MB flash or 64 MB RAM at most, on the way out as well
+/// `Sufficient` - more than both, fine for now
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceClass {
+    FourThirtyTwo,
+    EightSixtyFour,
+    Sufficient
+}
+
+impl ResourceClass {
+    /// Returns the class of a device with `flash_mb` of flash & `ram_mb` of RAM
+    pub fn of(flash_mb: u32, ram_mb: u32) -> Self {
+        if flash_mb <= 4 || ram_mb <= 32 {
+            ResourceClass::FourThirtyTwo
+        }
+        else if flash_mb <= 8 || ram_mb <= 64 {
+            ResourceClass::EightSixtyFour
+        }
+        else {
+            ResourceClass::Sufficient
+        }
+    }
+
+    /// Returns the warning badge shown in listings, `None` when there's nothing to warn about
+    pub fn badge(&self) -> Option<&'static str> {
+        match self {
+            ResourceClass::FourThirtyTwo => Some("⚠ 4/32"),
+            ResourceClass::EightSixtyFour => Some("⚠ 8/64"),
+            ResourceClass::Sufficient => None
+        }
+    }
+}
+
+impl Display for ResourceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceClass::FourThirtyTwo => write!(f, "4/32"),
+            ResourceClass::EightSixtyFour => write!(f, "8/64"),
+            ResourceClass::Sufficient => write!(f, "sufficient")
+        }
+    }
+}
+
+/// # `Resources` represents minimum flash & RAM sizes in MB a device must have, either is optional
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Resources {
+    pub flash_mb: Option<u32>,
+    pub ram_mb: Option<u32>
+}
+
+impl Resources {
+    /// Returns whether either minimum is set
+    pub fn is_set(&self) -> bool {
+        self.flash_mb.is_some() || self.ram_mb.is_some()
+    }
+
+    /// Returns whether `hardware` has at least these resources, unknown sizes never do
+    pub fn are_met_by(&self, hardware: Option<&Hardware>) -> bool {
+        let meets = |minimum: Option<u32>, size: Option<u32>| match (minimum, size) {
+            (None, _) => true,
+            (Some(minimum), Some(size)) => size >= minimum,
+            (Some(_), None) => false
+        };
+
+        meets(self.flash_mb, hardware.and_then(|hardware| hardware.flash_mb))
+            && meets(self.ram_mb, hardware.and_then(|hardware| hardware.ram_mb))
+    }
+}
+
+/// # `Hardware` represents technical data of a hardware revision, from the ToH dump or its device page
+///
+/// `soc` - system on chip, ex: Qualcomm Atheros QCA9558
+/// `flash_mb` - flash size in MB, the smallest one when variants differ
+/// `ram_mb` - RAM size in MB, the smallest one when variants differ
+/// `wlan_24ghz` - 2.4 GHz Wi-Fi standards, ex: b/g/n, empty without 2.4 GHz Wi-Fi
+/// `wlan_50ghz` - 5 GHz Wi-Fi standards, ex: a/n/ac, empty without 5 GHz Wi-Fi
+/// `ethernet_100m_ports` - number of 100 Mbit ethernet ports, WAN included
+/// `ethernet_1g_ports` - number of gigabit ethernet ports, WAN included
+///
+/// All fields are optional, device data is often incomplete
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Hardware {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub soc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flash_mb: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram_mb: Option<u32>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub wlan_24ghz: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub wlan_50ghz: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ethernet_100m_ports: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ethernet_1g_ports: Option<u32>
+}
+
+impl Hardware {
+    /// Returns the resource class, `None` when flash or RAM size is unknown
+    pub fn resource_class(&self) -> Option<ResourceClass> {
+        Some(ResourceClass::of(self.flash_mb?, self.ram_mb?))
+    }
+
+    /// Returns the number of ethernet ports, WAN included, `None` when unknown
+    pub fn ethernet_ports(&self) -> Option<u32> {
+        match (self.ethernet_100m_ports, self.ethernet_1g_ports) {
+            (None, None) => None,
+            (fast, gigabit) => Some(fast.unwrap_or(0) + gigabit.unwrap_or(0))
+        }
+    }
+
+    /// Returns flash & RAM as flash/RAM in MB, with `?` for unknown sizes, ex: 16/128
+    pub fn resources(&self) -> String {
+        let size = |size: Option<u32>| size.map(|size| size.to_string()).unwrap_or_else(|| "?".to_string());
+
+        format!("{}/{}", size(self.flash_mb), size(self.ram_mb))
+    }
+
+    /// Returns Wi-Fi radios, ex: 2.4 GHz b/g/n, 5 GHz a/n/ac
+    pub fn radios(&self) -> String {
+        let mut radios = Vec::new();
+
+        if !self.wlan_24ghz.is_empty() {
+            radios.push(format!("2.4 GHz {}", self.wlan_24ghz));
+        }
+
+        if !self.wlan_50ghz.is_empty() {
+            radios.push(format!("5 GHz {}", self.wlan_50ghz));
+        }
+
+        radios.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_resources() {
+        let hardware = |flash_mb, ram_mb| Hardware { flash_mb, ram_mb, ..Hardware::default() };
+
+        assert_eq!(Some(ResourceClass::FourThirtyTwo), hardware(Some(4), Some(64)).resource_class());
+        assert_eq!(Some(ResourceClass::FourThirtyTwo), hardware(Some(16), Some(32)).resource_class());
+        assert_eq!(Some(ResourceClass::EightSixtyFour), hardware(Some(8), Some(128)).resource_class());
+        assert_eq!(Some(ResourceClass::Sufficient), hardware(Some(16), Some(128)).resource_class());
+        assert_eq!(None, hardware(None, Some(128)).resource_class());
+        assert_eq!("16/?", hardware(Some(16), None).resources());
+
+        let minimum = Resources { flash_mb: Some(16), ram_mb: None };
+        assert!(minimum.are_met_by(Some(&hardware(Some(16), None))));
+        assert!(!minimum.are_met_by(Some(&hardware(Some(8), Some(128)))));
+        assert!(!minimum.are_met_by(None));
+        assert!(Resources::default().are_met_by(None));
+    }
+}
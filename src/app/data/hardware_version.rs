@@ -0,0 +1,118 @@
+/// Separators between hardware versions in a versions cell, line breaks included
+const SEPARATORS: [char; 4] = [',', '/', ';', '\n'];
+
+/// Widest range of hardware versions that's expanded, ex: v1-v20
+const MAX_RANGE: u32 = 20;
+
+/// Normalizes a hardware version, so that versions match regardless of case, spacing
+/// & trailing `.0`s
+///
+/// ## Example
+///
+/// "V5.0" -> "v5"
+/// "Rev 05" -> "rev05"
+pub fn normalize_version(version: &str) -> String {
+    let mut version = version.split_whitespace()
+        .collect::<String>()
+        .to_lowercase();
+
+    while let Some(stripped) = version.strip_suffix(".0") {
+        if !stripped.ends_with(|c: char| c.is_ascii_digit()) {
+            break;
+        }
+
+        version = stripped.to_string();
+    }
+
+    version
+}
+
+/// Splits a normalized version into its prefix & number, ex: "v12" -> ("v", 12)
+fn split_number(version: &str) -> Option<(&str, u32)> {
+    let index = version.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, number) = version.split_at(index);
+
+    number.parse()
+        .ok()
+        .map(|number| (prefix, number))
+}
+
+/// Expands a range of hardware versions, ex: "v1-v3" or "v1-3" -> ["v1", "v2", "v3"],
+/// `None` when `version` isn't a range
+fn expand_range(version: &str) -> Option<Vec<String>> {
+    let (start, end) = version.split_once('-')?;
+
+    // single letter versions, ex: "a-c"
+    if let ([start], [end]) = (start.as_bytes(), end.as_bytes()) {
+        if start.is_ascii_lowercase() && end.is_ascii_lowercase() && start < end {
+            return Some((*start..=*end)
+                .map(|c| (c as char).to_string())
+                .collect());
+        }
+    }
+
+    let (prefix, start) = split_number(start)?;
+    let (end_prefix, end) = split_number(end)?;
+
+    if (!end_prefix.is_empty() && end_prefix != prefix) || start >= end || end - start > MAX_RANGE {
+        return None;
+    }
+
+    Some((start..=end)
+        .map(|number| format!("{}{}", prefix, number))
+        .collect())
+}
+
+/// Parses the text of a versions cell into normalized hardware versions, see `normalize_version`
+///
+/// Versions may be separated by ",", "/", ";" or line breaks, & ranges like "v1-v3" are expanded
+///
+/// ## Example
+///
+/// "V1.0, v2 / v3-v5" -> ["v1", "v2", "v3", "v4", "v5"]
+pub fn parse_versions(text: &str) -> Vec<String> {
+    let text = text.trim();
+
+    if text.eq_ignore_ascii_case("n/a") || text == "-" {
+        return Vec::new();
+    }
+
+    let mut versions: Vec<String> = Vec::new();
+
+    for version in text.split(&SEPARATORS[..]).map(normalize_version) {
+        let expanded = expand_range(&version)
+            .unwrap_or_else(|| vec![version]);
+
+        for version in expanded {
+            if !version.is_empty() && !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
+    }
+
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_versions() {
+        assert_eq!("v5", normalize_version("V5.0"));
+        assert_eq!("v5", normalize_version(" v 5 "));
+        assert_eq!("v1.1", normalize_version("v1.1"));
+        assert_eq!("rev05", normalize_version("Rev 05"));
+        assert_eq!("2", normalize_version("2.0.0"));
+    }
+
+    #[test]
+    fn parses_versions_with_separators_and_ranges() {
+        assert_eq!(vec!["v1", "v2", "v3", "v4", "v5"], parse_versions("V1.0, v2 / v3-v5"));
+        assert_eq!(vec!["a1", "a2", "a3"], parse_versions("A1-3"));
+        assert_eq!(vec!["c", "d", "e"], parse_versions("C-E; d"));
+        assert_eq!(vec!["eu-v2", "v1-v40"], parse_versions("EU-v2, v1-v40"));
+        assert!(parse_versions("N/A").is_empty());
+        assert!(parse_versions(" ").is_empty());
+    }
+}
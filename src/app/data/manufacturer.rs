@@ -2,6 +2,9 @@ use std::fmt::{self, Display};
 use serde::{Serialize, Deserialize};
 use colored::*;
 use super::model::Model;
+use super::revision::Revision;
+use super::release::Registry;
+use super::normalize;
 
 /// # `Manufacturer` represents a router brand
 /// 
@@ -11,7 +14,7 @@ use super::model::Model;
 /// ## Example
 /// 
 /// Brand - TP-Link
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Manufacturer {
     name: String,
     models: Vec<Model>
@@ -37,20 +40,84 @@ impl Manufacturer {
         &self.models
     }
 
-    fn display_as_table(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    pub fn models_mut(&mut self) -> &mut Vec<Model> {
+        &mut self.models
+    }
+
+    /// Returns whether this manufacturer goes by `name`, see `normalize`
+    pub fn is_named(&self, name: &str) -> bool {
+        normalize(&self.name) == normalize(name)
+    }
+
+    /// Finds the model named `name`, see `normalize`
+    pub fn find_model(&self, name: &str) -> Option<&Model> {
+        self.models.iter()
+            .find(|model| normalize(model.name()) == normalize(name))
+    }
+
+    /// Finds the model named `name` along with its revision with hardware `version`
+    ///
+    /// Without a `version`, the revision with the latest supported release is picked
+    pub fn find_revision(&self, name: &str, version: Option<&str>) -> Option<(&Model, &Revision)> {
+        self.find_model(name)
+            .and_then(|model| model.find_revision(version).map(|revision| (model, revision)))
+    }
+
+    /// Returns this manufacturer displayed with lifecycle status of its models, see `WithStatus`
+    pub fn with_status<'m>(&'m self, registry: &'m Registry) -> WithStatus<'m> {
+        WithStatus { manufacturer: self, registry }
+    }
+
+    /// Returns whether technical data is known for any hardware revision, see `Hardware`
+    pub fn has_hardware(&self) -> bool {
+        self.models.iter()
+            .flat_map(|model| model.revisions())
+            .any(|revision| revision.hardware().is_some())
+    }
+
+    /// Keeps only hardware revisions `keep` is true for, models left without any are removed
+    pub fn retain_revisions<F: Fn(&Revision) -> bool>(&mut self, keep: F) {
+        for model in self.models.iter_mut() {
+            model.revisions_mut().retain(|revision| keep(revision));
+        }
+
+        self.models.retain(|model| !model.revisions().is_empty());
+    }
+
+    /// Writes models as a table, with a status column & rows colored by status of their
+    /// release when `registry` is given
+    ///
+    /// A flash/RAM column, with a badge on low resource revisions, is added when technical
+    /// data is known, see `ResourceClass`
+    fn display_as_table(&self, f: &mut fmt::Formatter<'_>, registry: Option<&Registry>) -> fmt::Result {
         let mut console_width = 100;
+        let has_hardware = self.has_hardware();
 
         let index_width_percent = 5;
         let model_width_percent = 15;
         let version_width_percent = 15;
-        let openwrt_version_width_percent = 35;
-        let device_page_width_percent = 30;
+        let mut openwrt_version_width_percent = 35;
+        let mut device_page_width_percent = 30;
+        let status_width_percent = 15;
+        let resources_width_percent = 13;
 
         let mut index_width = 5;
         let mut model_width = 15;
         let mut version_width = 15;
         let mut openwrt_version_width = 35;
         let mut device_page_width = 30;
+        let mut status_width = 15;
+        let mut resources_width = 13;
+
+        if registry.is_some() {
+            device_page_width_percent -= status_width_percent;
+            device_page_width -= status_width;
+        }
+
+        if has_hardware {
+            openwrt_version_width_percent -= resources_width_percent;
+            openwrt_version_width -= resources_width;
+        }
 
         if let Some((width, _)) = term_size::dimensions() {
             console_width = width;
@@ -60,10 +127,12 @@ impl Manufacturer {
             version_width = version_width_percent * width / 100;
             openwrt_version_width = openwrt_version_width_percent * width / 100;
             device_page_width = device_page_width_percent * width / 100;
+            status_width = status_width_percent * width / 100;
+            resources_width = resources_width_percent * width / 100;
         }
 
         write!(f, "{} {}", "Brand:".bold().bright_cyan(), self.name())?;
-        write!(f, "\n")?;
+        writeln!(f)?;
         write!(f, "{}", format!("Found {} model(s)!", self.count()).bright_green())?;
         write!(f, "\n\n")?;
 
@@ -74,7 +143,13 @@ impl Manufacturer {
             "OpenWrt Version".bold().bright_cyan(), openwrt_version_width,
             "Device Page".bold().bright_cyan(), device_page_width    
         )?;
-        write!(f, "\n")?;
+        if has_hardware {
+            write!(f, "{0:1$}", "Flash/RAM MB".bold().bright_cyan(), resources_width)?;
+        }
+        if registry.is_some() {
+            write!(f, "{0:1$}", "Status".bold().bright_cyan(), status_width)?;
+        }
+        writeln!(f)?;
         write!(f, "{0:<1$}{2:3$}{4:5$}{6:7$}{8:9$}",
             "", index_width,
             "-----".bold().bright_cyan(), model_width,
@@ -82,47 +157,85 @@ impl Manufacturer {
             "---------------".bold().bright_cyan(), openwrt_version_width,
             "-----------".bold().bright_cyan(), device_page_width    
         )?;
+        if has_hardware {
+            write!(f, "{0:1$}", "------------".bold().bright_cyan(), resources_width)?;
+        }
+        if registry.is_some() {
+            write!(f, "{0:1$}", "------".bold().bright_cyan(), status_width)?;
+        }
         write!(f, "\n\n")?;
 
-        let mut i = 1;
-
-        for model in self.models() {
-            let name = model.name();
-            let version = if model.versions().len() > 0 {
-                model.versions()
-                    .join(", ")
-                    .to_string()
-            } else {
-                "N/A".to_string()
-            };
-            let openwrt_version = if model.openwrt_version().link().len() > 0 {
-                model.openwrt_version().link()
-            } else {
-                "N/A"
-            };
-            let device_page = if model.device_page().len() > 0 {
-                model.device_page()
-            } else {
-                "N/A"
-            };
-
-            write!(f, "{0:<1$}{2:3$}{4:5$}{6:7$}{8:9$}",
-                format!("{}.", i), index_width,
-                name, model_width,
-                version, version_width,
-                openwrt_version, openwrt_version_width,
-                device_page, device_page_width    
-            )?;
-            write!(f, "\n")?;
-
-            i += 1;
+        for (i, model) in self.models().iter().enumerate() {
+            // revisions of a model are listed under it, with the model's name & index only once
+            for (j, revision) in model.revisions().iter().enumerate() {
+                let (index, name) = if j == 0 {
+                    (format!("{}.", i + 1), model.name().as_str())
+                } else {
+                    (String::new(), "")
+                };
+                let version = if !revision.versions_text().is_empty() {
+                    revision.versions_text()
+                } else {
+                    "N/A".to_string()
+                };
+                let openwrt_version = if !revision.openwrt_version().link().is_empty() {
+                    revision.openwrt_version().link()
+                } else {
+                    "N/A"
+                };
+                let device_page = if !revision.device_page().is_empty() {
+                    revision.device_page()
+                } else {
+                    "N/A"
+                };
+
+                let mut row = format!("{0:<1$}{2:3$}{4:5$}{6:7$}{8:9$}",
+                    index, index_width,
+                    name, model_width,
+                    version, version_width,
+                    openwrt_version, openwrt_version_width,
+                    device_page, device_page_width    
+                );
+
+                if has_hardware {
+                    // low resource revisions get a badge, ex: 8/32 ⚠ 4/32
+                    let resources = match revision.hardware() {
+                        Some(hardware) => match hardware.resource_class().and_then(|class| class.badge()) {
+                            Some(badge) => format!("{} {}", hardware.resources(), badge),
+                            None => hardware.resources()
+                        },
+                        None => "N/A".to_string()
+                    };
+
+                    row = format!("{0}{1:2$}", row, resources, resources_width);
+                }
+
+                match registry {
+                    Some(registry) => {
+                        // a model is annotated on its first row, by the best status of its revisions
+                        let annotation = match (j, registry.model_status(model)) {
+                            (0, Some(status)) => status.describe_model(),
+                            (0, None) => "N/A",
+                            _ => ""
+                        };
+                        let row = format!("{0}{1:2$}", row, annotation, status_width);
+
+                        match registry.revision_status(revision) {
+                            Some(status) => write!(f, "{}", status.paint(&row))?,
+                            None => write!(f, "{}", row)?
+                        }
+                    },
+                    None => write!(f, "{}", row)?
+                }
+                writeln!(f)?;
+            }
         }
         
-        write!(f, "\n")?;
+        writeln!(f)?;
         write!(f, "{}", format!("Found {} model(s)!", self.count()).bright_green())?;
         write!(f, "\n\n")?;
         write!(f, "{0:-^1$}", "", console_width)?;
-        write!(f, "\n")
+        writeln!(f)
     }
 }
 
@@ -131,7 +244,7 @@ impl Display for Manufacturer {
     /// if it fails, tries to json serialize `self`
     /// else, fallback to debug format    
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.display_as_table(f) {
+        match self.display_as_table(f, None) {
             Ok(val) => Ok(val),
             Err(_) => {
                 write!(f, "Brand: {}\nModels: {}", self.name, serde_json::to_string_pretty(&self.models)
@@ -139,4 +252,18 @@ impl Display for Manufacturer {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// # `WithStatus` represents a `Manufacturer` displayed along with lifecycle status of its models
+///
+/// Models are annotated as on current release, release candidate only, old stable only or EOL, see `Status`
+pub struct WithStatus<'m> {
+    manufacturer: &'m Manufacturer,
+    registry: &'m Registry
+}
+
+impl Display for WithStatus<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.manufacturer.display_as_table(f, Some(self.registry))
+    }
+}
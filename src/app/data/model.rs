@@ -1,50 +1,69 @@
 use std::fmt::{self, Display};
-use super::version::Version;
+use super::revision::Revision;
 use serde::{Serialize, Deserialize};
 
 /// # `Model` represents a model from a router brand
 /// 
-/// `name` - name of the brand
-/// `versions` - router versions, ex: V1, V2, A1, A2 etc.,
-///              this is brand specifc
-/// `openwrt_version` - `Version` is the currently supported OpenWrt release for this model
-/// `device_page` - link to detail information on this model
+/// `name` - name of the model
+/// `revisions` - hardware revisions of the model, each with its own supported release
+///               & device page, as listed on the devices page
 ///
 /// ## Example
 /// 
 /// Brand - TP-Link
 /// Model - WR841ND
-/// Versions - V10, V11
-/// OpenWrt Version - 19.07
-/// Device Page - https://openwrt.org/toh/tp-link/wr841nd
-#[derive(Serialize, Deserialize, Debug)]
+/// Revisions - V10, V11 (19.07), V9 (18.06)
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Model {
     name: String,
-    versions: Vec<String>,
-    openwrt_version: Version,
-    device_page: String
+    revisions: Vec<Revision>
 }
 
 impl Model {
     /// Creates a new instance of `Model` 
-    pub fn new(name: String, versions: Vec<String>, openwrt_version: Version, device_page: String) -> Self {
-        Self { name, versions, openwrt_version, device_page }
+    pub fn new(name: String, revisions: Vec<Revision>) -> Self {
+        Self { name, revisions }
     }
 
     pub fn name(&self) -> &String {
         &self.name
     }
 
-    pub fn versions(&self) -> &Vec<String> {
-        &self.versions
+    pub fn revisions(&self) -> &Vec<Revision> {
+        &self.revisions
     }
 
-    pub fn openwrt_version(&self) -> &Version {
-        &self.openwrt_version
+    pub fn revisions_mut(&mut self) -> &mut Vec<Revision> {
+        &mut self.revisions
     }
 
-    pub fn device_page(&self) -> &String {
-        &self.device_page
+    /// Adds another hardware revision of this model
+    pub fn add_revision(&mut self, revision: Revision) {
+        self.revisions.push(revision);
+    }
+
+    /// Returns the revision with the latest supported release
+    pub fn latest(&self) -> Option<&Revision> {
+        self.revisions.iter()
+            .max_by_key(|revision| revision.openwrt_version().release())
+    }
+
+    /// Finds the revision with hardware `version`, or the latest one without a `version`
+    pub fn find_revision(&self, version: Option<&str>) -> Option<&Revision> {
+        match version.filter(|version| !version.trim().is_empty()) {
+            Some(version) => self.revisions.iter()
+                .find(|revision| revision.has_version(version)),
+            None => self.latest()
+        }
+    }
+
+    /// Returns hardware versions of all revisions, as written on the devices page
+    pub fn versions_text(&self) -> String {
+        self.revisions.iter()
+            .map(|revision| revision.versions_text())
+            .filter(|versions| !versions.is_empty())
+            .collect::<Vec<String>>()
+            .join(", ")
     }
 }
 
@@ -54,4 +73,4 @@ impl Display for Model {
         write!(f, "{}", serde_json::to_string_pretty(&self)
             .unwrap_or(format!("{:#?}", self)))
     }
-}
\ No newline at end of file
+}
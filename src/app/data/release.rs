@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use colored::*;
+use serde::{Serialize, Deserialize};
+use super::release_number::ReleaseNumber;
+use super::revision::Revision;
+use super::model::Model;
+
+/// # `Status` represents where a release branch is in OpenWrt's support lifecycle
+///
+/// `Current` - current stable branch, gets fixes & new point releases
+/// `Upcoming` - branch with release candidates only, on the way to be current
+/// `OldStable` - previous stable branch, gets security fixes for a while
+/// `Eol` - branch reached its end of life, gets nothing anymore
+///
+/// Ordered from worst to best, an upcoming branch is better than an old stable one as it's newer,
+/// but not as good as the current one as it isn't released yet
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Eol,
+    OldStable,
+    Upcoming,
+    Current
+}
+
+impl Status {
+    /// Returns the annotation of a model, whose best supported release is in a branch of this status
+    pub fn describe_model(&self) -> &'static str {
+        match self {
+            Status::Current => "on current release",
+            Status::Upcoming => "release candidate only",
+            Status::OldStable => "old stable only",
+            Status::Eol => "EOL"
+        }
+    }
+
+    /// Colors `text` by this status, green for current, cyan for upcoming, yellow for old stable
+    /// & red for EOL
+    pub fn paint(&self, text: &str) -> ColoredString {
+        match self {
+            Status::Current => text.green(),
+            Status::Upcoming => text.cyan(),
+            Status::OldStable => text.yellow(),
+            Status::Eol => text.red()
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().replace(['-', '_'], " ").as_str() {
+            "current" | "current stable" | "stable" => Ok(Status::Current),
+            "upcoming" | "release candidate" | "rc" => Ok(Status::Upcoming),
+            "old stable" | "oldstable" => Ok(Status::OldStable),
+            "eol" | "end of life" => Ok(Status::Eol),
+            _ => Err(format!("Unknown release status: {}", text))
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Current => write!(f, "current"),
+            Status::Upcoming => write!(f, "upcoming"),
+            Status::OldStable => write!(f, "old stable"),
+            Status::Eol => write!(f, "EOL")
+        }
+    }
+}
+
+/// # `Release` represents an OpenWrt release, as listed on the releases page
+///
+/// `version` - release number, ex: 19.07.3
+/// `date` - release date, as YYYY-MM-DD, empty when unknown
+/// `status` - lifecycle status of its branch when stated, else worked out by `Registry`
+///
+/// ## Example
+///
+/// { "version": "19.07.3", "date": "2020-05-16" }
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Release {
+    version: String,
+    #[serde(default)]
+    date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<Status>
+}
+
+impl Release {
+    /// Creates a new instance of `Release`
+    pub fn new(version: String, date: String, status: Option<Status>) -> Self {
+        Self { version, date, status }
+    }
+
+    pub fn version(&self) -> &String {
+        &self.version
+    }
+
+    pub fn date(&self) -> &String {
+        &self.date
+    }
+
+    /// Returns the version as a `ReleaseNumber`, if it is one
+    pub fn number(&self) -> Option<ReleaseNumber> {
+        self.version.parse().ok()
+    }
+}
+
+/// # `Branch` represents a release branch, with its latest point release & status
+#[derive(Serialize, Debug, Clone)]
+pub struct Branch {
+    pub branch: String,
+    pub latest: Release,
+    pub status: Status
+}
+
+/// # `Registry` represents all known OpenWrt releases, grouped by branch
+///
+/// Unless stated by a release, the newest branch with a final release is current, the one
+/// before it is old stable & all others are EOL. Branches with release candidates only
+/// aren't ranked, they're upcoming when newer than all released ones, else they were
+/// abandoned & are EOL.
+#[derive(Debug, Default)]
+pub struct Registry {
+    branches: BTreeMap<String, Branch>
+}
+
+impl Registry {
+    /// Creates a new instance of `Registry` from `releases`, in any order
+    pub fn new(releases: Vec<Release>) -> Self {
+        let mut latest: BTreeMap<String, (ReleaseNumber, Release)> = BTreeMap::new();
+        let mut stated: BTreeMap<String, Status> = BTreeMap::new();
+
+        for release in releases {
+            let number = match release.number() {
+                Some(number) => number,
+                None => continue
+            };
+            let branch = number.branch();
+
+            if let Some(status) = release.status {
+                stated.insert(branch.clone(), status);
+            }
+
+            match latest.get(&branch) {
+                Some((current, _)) if *current >= number => (),
+                _ => {
+                    latest.insert(branch, (number, release));
+                }
+            }
+        }
+
+        // newest released branches first, see `ReleaseNumber`'s ordering
+        let mut released = latest.iter()
+            .filter(|(_, (number, _))| !number.is_pre_release())
+            .map(|(branch, (number, _))| (branch.clone(), number.clone()))
+            .collect::<Vec<(String, ReleaseNumber)>>();
+
+        released.sort_by(|(_, this), (_, that)| that.cmp(this));
+
+        let newest = released.first()
+            .map(|(_, number)| number.clone());
+        let ranks = released.into_iter()
+            .map(|(branch, _)| branch)
+            .enumerate()
+            .map(|(i, branch)| (branch, match i {
+                0 => Status::Current,
+                1 => Status::OldStable,
+                _ => Status::Eol
+            }))
+            .collect::<BTreeMap<String, Status>>();
+
+        let branches = latest.into_iter()
+            .map(|(branch, (number, release))| {
+                let upcoming = newest.as_ref().is_none_or(|newest| number > *newest);
+                let status = stated.get(&branch)
+                    .or_else(|| ranks.get(&branch))
+                    .copied()
+                    .unwrap_or(if upcoming { Status::Upcoming } else { Status::Eol });
+
+                (branch.clone(), Branch { branch, latest: release, status })
+            })
+            .collect();
+
+        Self { branches }
+    }
+
+    /// Returns all branches, newest first
+    pub fn branches(&self) -> Vec<&Branch> {
+        let mut branches = self.branches.values()
+            .collect::<Vec<&Branch>>();
+
+        branches.sort_by_key(|branch| std::cmp::Reverse(branch.latest.number()));
+
+        branches
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty()
+    }
+
+    /// Returns the branch `release` belongs to, if it's known
+    pub fn branch(&self, release: &ReleaseNumber) -> Option<&Branch> {
+        self.branches.get(&release.branch())
+    }
+
+    /// Returns status of the branch supporting `revision`, `None` when it has no known release
+    pub fn revision_status(&self, revision: &Revision) -> Option<Status> {
+        revision.openwrt_version()
+            .release()
+            .and_then(|release| self.branch(&release))
+            .map(|branch| branch.status)
+    }
+
+    /// Returns status of `model`, the best one of its revisions
+    ///
+    /// ## Example
+    ///
+    /// v1 on 17.01 (EOL), v2 on 19.07 (old stable) -> old stable
+    pub fn model_status(&self, model: &Model) -> Option<Status> {
+        model.revisions()
+            .iter()
+            .filter_map(|revision| self.revision_status(revision))
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::fixtures::{model, revision};
+
+    fn release(version: &str, date: &str) -> Release {
+        Release::new(version.to_string(), date.to_string(), None)
+    }
+
+    fn registry() -> Registry {
+        Registry::new(vec![
+            release("19.07.3", "2020-05-16"),
+            release("18.06.8", "2020-02-27"),
+            release("19.07.0-rc1", "2019-09-24"),
+            release("17.01.7", "2019-06-20"),
+            release("19.07.2", "2020-03-07"),
+            release("21.02.0-rc1", "2021-05-03"),
+            release("snapshot", "")
+        ])
+    }
+
+    #[test]
+    fn ranks_branches_by_release() {
+        let registry = registry();
+        let branches = registry.branches()
+            .into_iter()
+            .map(|branch| (branch.branch.as_str(), branch.latest.version().as_str(), branch.status))
+            .collect::<Vec<(&str, &str, Status)>>();
+
+        assert_eq!(vec![
+            ("21.02", "21.02.0-rc1", Status::Upcoming),
+            ("19.07", "19.07.3", Status::Current),
+            ("18.06", "18.06.8", Status::OldStable),
+            ("17.01", "17.01.7", Status::Eol)
+        ], branches);
+    }
+
+    #[test]
+    fn prefers_stated_status() {
+        let registry = Registry::new(vec![
+            release("19.07.3", "2020-05-16"),
+            Release::new("18.06.8".to_string(), String::new(), Some("end-of-life".parse().unwrap()))
+        ]);
+
+        assert_eq!(Some(Status::Eol), registry.branch(&"18.06.1".parse().unwrap()).map(|branch| branch.status));
+    }
+
+    #[test]
+    fn annotates_models_by_best_revision() {
+        let registry = registry();
+        let archer_c7 = model("Archer C7", vec![revision(&["v1"], "17.01.7"), revision(&["v2"], "18.06.4")]);
+
+        assert_eq!(Some(Status::OldStable), registry.model_status(&archer_c7));
+        assert_eq!("old stable only", registry.model_status(&archer_c7).unwrap().describe_model());
+        assert_eq!(None, registry.model_status(&model("TL-WR703N", vec![revision(&[], "")])));
+
+        let archer_c7 = model("Archer C7", vec![revision(&["v1"], "18.06.8"), revision(&["v2"], "21.02.0-rc1")]);
+        assert_eq!(Some(Status::Upcoming), registry.model_status(&archer_c7));
+        assert_eq!(Some(Status::Current), registry.model_status(&model("R7800", vec![revision(&[], "19.07.3"), revision(&[], "21.02.0-rc1")])));
+    }
+}
@@ -0,0 +1,153 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// # `ReleaseNumber` represents an OpenWrt release number, ordered the way releases are
+///
+/// `components` - numeric parts of the release, ex: 19, 07, 3 for 19.07.3
+/// `pre_release` - suffix of a release candidate, ex: rc1 for 19.07.0-rc1
+///
+/// A branch sorts before its point releases, & release candidates sort before the release
+///
+/// ## Example
+///
+/// 18.06.8 < 19.07 < 19.07.0-rc1 < 19.07.0 < 19.07.3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseNumber {
+    components: Vec<u32>,
+    pre_release: Option<String>
+}
+
+impl ReleaseNumber {
+    /// Returns the branch this release belongs to, ex: 19.07 for 19.07.3
+    pub fn branch(&self) -> String {
+        self.components.iter()
+            .take(2)
+            .map(|component| format!("{:02}", component))
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+
+    /// Returns whether this is a release candidate, ex: 19.07.0-rc1
+    pub fn is_pre_release(&self) -> bool {
+        self.pre_release.is_some()
+    }
+
+    /// Compares with `other` only as precisely as `other` is written, so a branch takes in
+    /// its point releases, ex: 19.07.3 equals 19.07 this way, while it's newer than 19.07.0-rc1
+    pub fn cmp_up_to(&self, other: &Self) -> Ordering {
+        if other.pre_release.is_some() || other.components.len() >= self.components.len() {
+            return self.cmp(other);
+        }
+
+        self.components[..other.components.len()].cmp(&other.components)
+    }
+}
+
+impl FromStr for ReleaseNumber {
+    type Err = String;
+
+    /// Parses releases like 19.07, 19.07.3 or 19.07.0-rc1, leading `v` & spaces are ignored
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim()
+            .trim_start_matches(['v', 'V']);
+        let (number, pre_release) = match text.find('-') {
+            Some(index) => (&text[..index], Some(text[index + 1..].to_lowercase())),
+            None => (text, None)
+        };
+        let components = number.split('.')
+            .map(|component| component.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|_| format!("Invalid release: {}", text))?;
+
+        Ok(Self { components, pre_release })
+    }
+}
+
+impl Ord for ReleaseNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.components.cmp(&other.components)
+            .then_with(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(this), Some(that)) => pre_release_key(this).cmp(&pre_release_key(that))
+            })
+    }
+}
+
+/// Splits a release candidate suffix into its label & its number, so the number is compared
+/// numerically, ex: rc, 10 for rc10, which sorts after rc2. The suffix is kept as a tie-breaker
+/// for numbers that aren't plain
+fn pre_release_key(pre_release: &str) -> (&str, Option<u32>, &str) {
+    let index = pre_release.find(|c: char| c.is_ascii_digit())
+        .unwrap_or(pre_release.len());
+    let (label, number) = pre_release.split_at(index);
+
+    (label, number.parse().ok(), number)
+}
+
+impl PartialOrd for ReleaseNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for ReleaseNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.components.iter()
+            .enumerate()
+            .map(|(i, component)| if i == 1 { format!("{:02}", component) } else { component.to_string() })
+            .collect::<Vec<String>>()
+            .join("."))?;
+
+        if let Some(pre_release) = &self.pre_release {
+            write!(f, "-{}", pre_release)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(text: &str) -> ReleaseNumber {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn orders_releases() {
+        assert!(release("18.06.8") < release("19.07"));
+        assert!(release("19.07") < release("19.07.0-rc1"));
+        assert!(release("19.07.0-rc1") < release("19.07.0-rc2"));
+        assert!(release("21.02.0-rc2") < release("21.02.0-rc10"));
+        assert!(release("21.02.0-beta3") < release("21.02.0-rc1"));
+        assert!(release("19.07.0-rc2") < release("19.07.0"));
+        assert!(release("19.07.0") < release("19.07.3"));
+        assert!(release("10.03.1") < release("12.09"));
+        assert_eq!(release("19.07.3"), release("v19.07.3"));
+    }
+
+    #[test]
+    fn compares_up_to_precision() {
+        assert_eq!(Ordering::Equal, release("19.07.3").cmp_up_to(&release("19.07")));
+        assert_eq!(Ordering::Equal, release("19.07.0-rc1").cmp_up_to(&release("19.07")));
+        assert_eq!(Ordering::Greater, release("19.07.3").cmp_up_to(&release("19.07.0-rc1")));
+        assert_eq!(Ordering::Less, release("18.06.8").cmp_up_to(&release("19")));
+        assert_eq!(Ordering::Less, release("19.07").cmp_up_to(&release("19.07.3")));
+    }
+
+    #[test]
+    fn returns_release_branch() {
+        assert_eq!("19.07", release("19.07.3").branch());
+        assert_eq!("10.03", release("10.03").branch());
+    }
+
+    #[test]
+    fn doesnt_parse_invalid_releases() {
+        assert!("snapshot".parse::<ReleaseNumber>().is_err());
+        assert!("".parse::<ReleaseNumber>().is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::{self, Display};
+use serde::{Serialize, Deserialize};
+use super::version::Version;
+use super::hardware::Hardware;
+use super::normalize_version;
+
+/// # `Revision` represents hardware revisions of a model sharing a row on the devices page
+///
+/// `versions` - hardware versions, ex: V1, V2, A1, A2 etc.,
+///              this is brand specifc, normalized for lookups
+/// `versions_text` - hardware versions as written on the devices page, for display
+/// `openwrt_version` - `Version` is the currently supported OpenWrt release for these versions
+/// `device_page` - link to detail information on these versions
+/// `hardware` - technical data of these versions, when known, see `Hardware`
+///
+/// ## Example
+///
+/// Versions - V10, V11
+/// OpenWrt Version - 19.07
+/// Device Page - https://openwrt.org/toh/tp-link/wr841nd
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Revision {
+    versions: Vec<String>,
+    #[serde(default)]
+    versions_text: String,
+    openwrt_version: Version,
+    #[serde(default)]
+    device_page: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hardware: Option<Hardware>
+}
+
+impl Revision {
+    /// Creates a new instance of `Revision`
+    pub fn new(versions: Vec<String>, openwrt_version: Version, device_page: String) -> Self {
+        let versions_text = versions.join(", ");
+
+        Self { versions, versions_text, openwrt_version, device_page, hardware: None }
+    }
+
+    /// Sets versions as written on the devices page, when they differ from normalized `versions`
+    pub fn with_versions_text(mut self, versions_text: String) -> Self {
+        self.versions_text = versions_text;
+
+        self
+    }
+
+    pub fn versions(&self) -> &Vec<String> {
+        &self.versions
+    }
+
+    /// Returns versions as written on the devices page, falling back to normalized ones
+    pub fn versions_text(&self) -> String {
+        if self.versions_text.is_empty() {
+            self.versions.join(", ")
+        }
+        else {
+            self.versions_text.clone()
+        }
+    }
+
+    /// Returns whether hardware `version` is one of these, see `normalize_version`
+    pub fn has_version(&self, version: &str) -> bool {
+        self.versions.iter()
+            .any(|val| normalize_version(val) == normalize_version(version))
+    }
+
+    pub fn openwrt_version(&self) -> &Version {
+        &self.openwrt_version
+    }
+
+    pub fn device_page(&self) -> &String {
+        &self.device_page
+    }
+
+    pub fn hardware(&self) -> Option<&Hardware> {
+        self.hardware.as_ref()
+    }
+
+    /// Sets technical data of these versions
+    pub fn set_hardware(&mut self, hardware: Hardware) {
+        self.hardware = Some(hardware);
+    }
+}
+
+impl Display for Revision {
+    /// Tries to json serialize `self`, else fallback  to debug format
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", serde_json::to_string_pretty(&self)
+            .unwrap_or(format!("{:#?}", self)))
+    }
+}
@@ -1,27 +1,42 @@
 use std::fmt::{self, Display};
 use serde::{Serialize, Deserialize};
+use super::release_number::ReleaseNumber;
 
 /// # `Version` represents some kind of version & a possible link to a version page
+///
+/// `version` - version as written, ex: 19.07.2
+/// `link` - link to the version page, empty when there's none
+///
+/// Serialized with named fields, see `schema`. Catalogs written before those loaded it
+/// from a `[version, link]` array, which still deserializes.
 /// 
 /// ## Example
 /// 
-/// Version("19.07", "https://openwrt.org/releases/19.07.2")
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Version (String, String);
+/// { "version": "19.07.2", "link": "https://openwrt.org/releases/19.07.2" }
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Version {
+    version: String,
+    #[serde(default)]
+    link: String
+}
 
 impl Version {
     /// Creates a new instance of `Version`
     pub fn new(version: String, link: String) -> Self {
-        Self(version, link)
+        Self { version, link }
     }
 
-    #[allow(dead_code)]
     pub fn version(&self) -> &String {
-        &self.0
+        &self.version
+    }
+
+    /// Returns the version as a `ReleaseNumber`, if it is one
+    pub fn release(&self) -> Option<ReleaseNumber> {
+        self.version.parse().ok()
     }
 
     pub fn link(&self) -> &String {
-        &self.1
+        &self.link
     }
 }
 
@@ -0,0 +1,289 @@
+use std::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use sha2::{Digest, Sha256};
+
+use super::data::Manufacturer;
+
+/// Version of the database schema, kept in `PRAGMA user_version`
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Tables are keyed by natural keys, so exports upsert into them. `first_snapshot_id` &
+/// `last_snapshot_id` track when a row was seen, `support` keeps the supported release
+/// of every hardware version per snapshot.
+const SCHEMA: &str = "
+    CREATE TABLE snapshots (
+        id INTEGER PRIMARY KEY,
+        source TEXT NOT NULL,
+        content_hash TEXT NOT NULL UNIQUE,
+        first_exported_at INTEGER NOT NULL,
+        last_exported_at INTEGER NOT NULL,
+        brands INTEGER NOT NULL,
+        models INTEGER NOT NULL
+    );
+
+    CREATE TABLE manufacturers (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL COLLATE NOCASE UNIQUE,
+        first_snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+        last_snapshot_id INTEGER NOT NULL REFERENCES snapshots (id)
+    );
+
+    CREATE TABLE models (
+        id INTEGER PRIMARY KEY,
+        manufacturer_id INTEGER NOT NULL REFERENCES manufacturers (id),
+        name TEXT NOT NULL COLLATE NOCASE,
+        first_snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+        last_snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+        UNIQUE (manufacturer_id, name)
+    );
+
+    CREATE TABLE hardware_versions (
+        id INTEGER PRIMARY KEY,
+        model_id INTEGER NOT NULL REFERENCES models (id),
+        version TEXT NOT NULL COLLATE NOCASE,
+        first_snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+        last_snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+        UNIQUE (model_id, version)
+    );
+
+    CREATE TABLE releases (
+        id INTEGER PRIMARY KEY,
+        version TEXT NOT NULL UNIQUE,
+        branch TEXT,
+        link TEXT NOT NULL
+    );
+
+    CREATE TABLE support (
+        snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+        hardware_version_id INTEGER NOT NULL REFERENCES hardware_versions (id),
+        release_id INTEGER REFERENCES releases (id),
+        device_page TEXT NOT NULL,
+        PRIMARY KEY (snapshot_id, hardware_version_id)
+    );
+
+    CREATE INDEX support_hardware_version ON support (hardware_version_id);
+    CREATE INDEX support_release ON support (release_id);
+    CREATE INDEX releases_branch ON releases (branch);
+";
+
+/// # `Export` represents the outcome of exporting the catalog
+///
+/// `snapshot_id` - id of the snapshot the catalog was recorded as
+/// `is_new` - whether the catalog changed since the previous exports
+#[derive(Debug)]
+pub struct Export {
+    pub snapshot_id: i64,
+    pub is_new: bool,
+    pub brands: usize,
+    pub models: usize
+}
+
+/// Creates the schema in an empty database, refuses databases of a newer schema
+fn migrate(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    match version {
+        0 => {
+            conn.execute_batch(SCHEMA)?;
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        },
+        SCHEMA_VERSION => (),
+        _ => return Err(format!("Database schema version {} is newer than supported version {}!", version, SCHEMA_VERSION).into())
+    }
+
+    Ok(())
+}
+
+/// Returns sha256 of `manufacturers` as JSON
+fn content_hash(manufacturers: &[Manufacturer]) -> Result<String, Box<dyn Error>> {
+    let content = serde_json::to_vec(manufacturers)?;
+
+    Ok(format!("{:x}", Sha256::digest(&content)))
+}
+
+/// Upserts the snapshot of content `hash`, returns its id & whether it's new
+fn upsert_snapshot(tx: &Transaction, manufacturers: &[Manufacturer], source: &str, hash: &str) -> Result<(i64, bool), Box<dyn Error>> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)?
+        .as_secs() as i64;
+    let existing: Option<i64> = tx.query_row("SELECT id FROM snapshots WHERE content_hash = ?1", [hash], |row| row.get(0))
+        .optional()?;
+
+    if let Some(id) = existing {
+        tx.execute("UPDATE snapshots SET last_exported_at = ?1 WHERE id = ?2", params![now, id])?;
+
+        return Ok((id, false));
+    }
+
+    let models = manufacturers.iter()
+        .map(|manufacturer| manufacturer.count())
+        .sum::<usize>();
+
+    tx.execute(
+        "INSERT INTO snapshots (source, content_hash, first_exported_at, last_exported_at, brands, models)
+            VALUES (?1, ?2, ?3, ?3, ?4, ?5)",
+        params![source, hash, now, manufacturers.len() as i64, models as i64]
+    )?;
+
+    Ok((tx.last_insert_rowid(), true))
+}
+
+/// Exports `manufacturers`, loaded from `source`, into `conn`
+///
+/// Exporting the same catalog again only bumps its snapshot's `last_exported_at`
+pub fn export(conn: &mut Connection, manufacturers: &[Manufacturer], source: &str) -> Result<Export, Box<dyn Error>> {
+    migrate(conn)?;
+
+    let hash = content_hash(manufacturers)?;
+    let tx = conn.transaction()?;
+    let (snapshot_id, is_new) = upsert_snapshot(&tx, manufacturers, source, &hash)?;
+    let mut models = 0;
+
+    {
+        let mut upsert_manufacturer = tx.prepare(
+            "INSERT INTO manufacturers (name, first_snapshot_id, last_snapshot_id) VALUES (?1, ?2, ?2)
+                ON CONFLICT (name) DO UPDATE SET last_snapshot_id = MAX(last_snapshot_id, excluded.last_snapshot_id)
+                RETURNING id"
+        )?;
+        let mut upsert_model = tx.prepare(
+            "INSERT INTO models (manufacturer_id, name, first_snapshot_id, last_snapshot_id) VALUES (?1, ?2, ?3, ?3)
+                ON CONFLICT (manufacturer_id, name) DO UPDATE SET last_snapshot_id = MAX(last_snapshot_id, excluded.last_snapshot_id)
+                RETURNING id"
+        )?;
+        let mut upsert_hardware_version = tx.prepare(
+            "INSERT INTO hardware_versions (model_id, version, first_snapshot_id, last_snapshot_id) VALUES (?1, ?2, ?3, ?3)
+                ON CONFLICT (model_id, version) DO UPDATE SET last_snapshot_id = MAX(last_snapshot_id, excluded.last_snapshot_id)
+                RETURNING id"
+        )?;
+        let mut upsert_release = tx.prepare(
+            "INSERT INTO releases (version, branch, link) VALUES (?1, ?2, ?3)
+                ON CONFLICT (version) DO UPDATE SET link = excluded.link
+                RETURNING id"
+        )?;
+        let mut upsert_support = tx.prepare(
+            "INSERT INTO support (snapshot_id, hardware_version_id, release_id, device_page) VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (snapshot_id, hardware_version_id) DO UPDATE SET
+                    release_id = excluded.release_id,
+                    device_page = excluded.device_page"
+        )?;
+
+        for manufacturer in manufacturers {
+            let manufacturer_id: i64 = upsert_manufacturer.query_row(params![manufacturer.name(), snapshot_id], |row| row.get(0))?;
+
+            for model in manufacturer.models() {
+                let model_id: i64 = upsert_model.query_row(params![manufacturer_id, model.name(), snapshot_id], |row| row.get(0))?;
+
+                for revision in model.revisions() {
+                    let openwrt_version = revision.openwrt_version();
+                    let release_id: Option<i64> = if openwrt_version.version().is_empty() {
+                        None
+                    }
+                    else {
+                        let branch = openwrt_version.release().map(|release| release.branch());
+
+                        Some(upsert_release.query_row(params![openwrt_version.version(), branch, openwrt_version.link()], |row| row.get(0))?)
+                    };
+                    // revisions without hardware versions get a single, unnamed one
+                    let versions = if revision.versions().is_empty() {
+                        vec![String::new()]
+                    }
+                    else {
+                        revision.versions().clone()
+                    };
+
+                    for version in versions {
+                        let hardware_version_id: i64 = upsert_hardware_version.query_row(params![model_id, version, snapshot_id], |row| row.get(0))?;
+
+                        upsert_support.execute(params![snapshot_id, hardware_version_id, release_id, revision.device_page()])?;
+                    }
+                }
+
+                models += 1;
+            }
+        }
+    }
+
+    tx.commit()?;
+
+    Ok(Export { snapshot_id, is_new, brands: manufacturers.len(), models })
+}
+
+/// Exports `manufacturers`, loaded from `source`, into SQLite database at `path`
+pub fn export_sqlite(manufacturers: &[Manufacturer], source: &str, path: &str) -> Result<Export, Box<dyn Error>> {
+    let path = shellexpand::tilde(path);
+    let mut conn = Connection::open(path.trim())
+        .map_err(|err| format!("{}: {}", path, err))?;
+
+    export(&mut conn, manufacturers, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::{manufacturers, manufacturers_on};
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn exports_normalized_tables() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let export = export(&mut conn, &manufacturers(), "devices.html").unwrap();
+
+        assert!(export.is_new);
+        assert_eq!(6, export.models);
+        assert_eq!(2, count(&conn, "manufacturers"));
+        assert_eq!(6, count(&conn, "models"));
+        assert_eq!(9, count(&conn, "hardware_versions"));
+        assert_eq!(3, count(&conn, "releases"));
+        assert_eq!(9, count(&conn, "support"));
+
+        let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(SCHEMA_VERSION, version);
+
+        let branch: String = conn.query_row(
+            "SELECT r.branch FROM support s
+                JOIN hardware_versions h ON h.id = s.hardware_version_id
+                JOIN releases r ON r.id = s.release_id
+                WHERE h.version = 'v9'",
+            [],
+            |row| row.get(0)
+        ).unwrap();
+        assert_eq!("18.06", branch);
+    }
+
+    #[test]
+    fn accumulates_history_across_exports() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        let first = export(&mut conn, &manufacturers_on("19.07.2"), "devices.html").unwrap();
+        let again = export(&mut conn, &manufacturers_on("19.07.2"), "devices.html").unwrap();
+        assert_eq!(first.snapshot_id, again.snapshot_id);
+        assert!(!again.is_new);
+        assert_eq!(1, count(&conn, "snapshots"));
+        assert_eq!(9, count(&conn, "support"));
+
+        let changed = export(&mut conn, &manufacturers(), "devices.html").unwrap();
+        assert!(changed.is_new);
+        assert_eq!(2, count(&conn, "snapshots"));
+        assert_eq!(4, count(&conn, "releases"));
+        assert_eq!(9, count(&conn, "hardware_versions"));
+        assert_eq!(18, count(&conn, "support"));
+
+        let releases: Vec<String> = conn.prepare(
+            "SELECT r.version FROM support s
+                JOIN hardware_versions h ON h.id = s.hardware_version_id
+                JOIN releases r ON r.id = s.release_id
+                WHERE h.version = 'v2' ORDER BY s.snapshot_id"
+        )
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(vec!["19.07.2", "19.07.3"], releases);
+    }
+}
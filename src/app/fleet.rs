@@ -0,0 +1,287 @@
+use std::error::Error;
+use std::io;
+use colored::*;
+use serde::{Serialize, Deserialize};
+
+use super::data::{self, Manufacturer, ReleaseNumber};
+
+/// # `Device` represents a router of the fleet, as listed in the inventory
+///
+/// `brand` - brand of the router
+/// `model` - model of the router
+/// `version` - hardware version of the router, if known
+/// `release` - OpenWrt release installed on the router, if known
+///
+/// ## Example
+///
+/// brand,model,version,release
+/// TP-Link,Archer C7,v2,18.06.8
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Device {
+    brand: String,
+    model: String,
+    #[serde(default, alias = "hw_version", alias = "hardware_version")]
+    version: String,
+    #[serde(default, alias = "installed_release", alias = "openwrt_release", alias = "openwrt_version")]
+    release: String
+}
+
+/// # `Status` represents how a device stands against the catalog
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// installed release is the latest supported one, or newer
+    UpToDate,
+    /// a newer supported release is available
+    Behind,
+    /// device is in the catalog, without any supported release
+    Unsupported,
+    /// installed release is missing or can't be compared
+    UnknownRelease,
+    /// brand, model or hardware version isn't in the catalog
+    UnknownDevice
+}
+
+impl Status {
+    /// Every status, in the order they're summed up
+    const ALL: [Status; 5] = [Status::UpToDate, Status::Behind, Status::Unsupported, Status::UnknownRelease, Status::UnknownDevice];
+
+    fn name(self) -> &'static str {
+        match self {
+            Status::UpToDate => "up to date",
+            Status::Behind => "behind",
+            Status::Unsupported => "unsupported",
+            Status::UnknownRelease => "unknown release",
+            Status::UnknownDevice => "unknown device"
+        }
+    }
+
+    fn label(self) -> ColoredString {
+        match self {
+            Status::UpToDate => self.name().green(),
+            Status::Behind | Status::UnknownRelease => self.name().yellow(),
+            Status::Unsupported | Status::UnknownDevice => self.name().red()
+        }
+    }
+}
+
+/// # `ReportEntry` represents a device of the fleet, matched against the catalog
+#[derive(Serialize, Debug)]
+pub struct ReportEntry {
+    device: Device,
+    status: Status,
+    latest_release: Option<String>,
+    device_page: Option<String>
+}
+
+/// Loads devices from inventory csv at `file`
+///
+/// Headers are matched regardless of case & spacing, so `Brand`, `Model`, `HW Version`
+/// & `Installed Release` are as good as `brand,model,version,release`
+pub fn load_inventory(file: &str) -> Result<Vec<Device>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(shellexpand::tilde(file).trim())?;
+    let headers = reader.headers()?
+        .iter()
+        .map(|header| data::normalize(header).replace(' ', "_"))
+        .collect::<csv::StringRecord>();
+
+    reader.set_headers(headers);
+
+    let mut devices = Vec::new();
+
+    for device in reader.deserialize() {
+        devices.push(device?);
+    }
+
+    Ok(devices)
+}
+
+/// Matches every device in `devices` against the catalog in `manufacturers`
+pub fn report(manufacturers: &[Manufacturer], devices: Vec<Device>) -> Vec<ReportEntry> {
+    devices.into_iter()
+        .map(|device| {
+            let revision = data::find_manufacturer(manufacturers, &device.brand)
+                .and_then(|manufacturer| manufacturer.find_revision(&device.model, Some(&device.version)));
+
+            let revision = match revision {
+                Some((_, revision)) => revision,
+                None => {
+                    return ReportEntry { device, status: Status::UnknownDevice, latest_release: None, device_page: None };
+                }
+            };
+
+            let latest_release = revision.openwrt_version().release();
+            let installed_release = device.release.parse::<ReleaseNumber>().ok();
+            let status = match (&latest_release, &installed_release) {
+                (None, _) => Status::Unsupported,
+                (Some(_), None) => Status::UnknownRelease,
+                (Some(latest), Some(installed)) if installed < latest => Status::Behind,
+                (Some(_), Some(_)) => Status::UpToDate
+            };
+            let device_page = Some(revision.device_page().to_string())
+                .filter(|device_page| !device_page.is_empty());
+
+            ReportEntry {
+                device,
+                status,
+                latest_release: latest_release.map(|release| release.to_string()),
+                device_page
+            }
+        })
+        .collect()
+}
+
+/// Prints `entries` as a table, sized to the widest value of each column
+pub fn print_table(entries: &[ReportEntry]) {
+    let headers = ["Brand", "Model", "Version", "Installed", "Latest", "Status"];
+    let rows = entries.iter()
+        .map(|entry| [
+            entry.device.brand.clone(),
+            entry.device.model.clone(),
+            entry.device.version.clone(),
+            entry.device.release.clone(),
+            entry.latest_release.clone().unwrap_or_else(|| "N/A".to_string())
+        ])
+        .collect::<Vec<[String; 5]>>();
+    let mut widths = headers.iter()
+        .map(|header| header.len())
+        .collect::<Vec<usize>>();
+
+    for row in &rows {
+        for (i, val) in row.iter().enumerate() {
+            widths[i] = widths[i].max(val.chars().count());
+        }
+    }
+
+    for (i, header) in headers.iter().enumerate() {
+        print!("{0:1$}  ", header.bold().bright_cyan(), widths[i]);
+    }
+    println!();
+
+    for (row, entry) in rows.iter().zip(entries) {
+        for (i, val) in row.iter().enumerate() {
+            print!("{0:1$}  ", val, widths[i]);
+        }
+        println!("{}", entry.status.label());
+    }
+
+    println!();
+    println!("{}", summary(entries));
+}
+
+/// Sums up `entries` with the number of devices of every status, ex:
+/// "3 device(s), 1 up to date, 1 behind, 0 unsupported, 0 unknown release, 1 unknown device"
+fn summary(entries: &[ReportEntry]) -> String {
+    let counts = Status::ALL.iter()
+        .map(|status| {
+            let count = entries.iter()
+                .filter(|entry| entry.status == *status)
+                .count();
+
+            format!("{} {}", count, status.name())
+        })
+        .collect::<Vec<String>>();
+
+    format!("{} device(s), {}", entries.len(), counts.join(", "))
+}
+
+/// Prints `entries` as csv, with a header row
+pub fn print_csv(entries: &[ReportEntry]) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_writer(io::stdout());
+
+    writer.write_record(["brand", "model", "version", "release", "status", "latest_release", "device_page"])?;
+
+    for entry in entries {
+        let status = serde_json::to_value(entry.status)?;
+
+        writer.write_record([
+            entry.device.brand.as_str(),
+            entry.device.model.as_str(),
+            entry.device.version.as_str(),
+            entry.device.release.as_str(),
+            status.as_str().unwrap_or_default(),
+            entry.latest_release.as_deref().unwrap_or_default(),
+            entry.device_page.as_deref().unwrap_or_default()
+        ])?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Prints `entries` as pretty printed JSON
+pub fn print_json(entries: &[ReportEntry]) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(entries)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::manufacturers;
+
+    fn device(brand: &str, model: &str, version: &str, release: &str) -> Device {
+        Device {
+            brand: brand.to_string(),
+            model: model.to_string(),
+            version: version.to_string(),
+            release: release.to_string()
+        }
+    }
+
+    #[test]
+    fn reports_devices_behind_latest_release() {
+        let entries = report(&manufacturers(), vec![
+            device("tp-link", "archer  c7", "V2", "18.06.8"),
+            device("TP-Link", "Archer C7", "v1", "17.01.7"),
+            device("TP-Link", "Archer C7", "", "19.07.3")
+        ]);
+
+        assert_eq!(Status::Behind, entries[0].status);
+        assert_eq!(Some("19.07.3".to_string()), entries[0].latest_release);
+        assert_eq!(Status::UpToDate, entries[1].status);
+        assert_eq!(Some("17.01.7".to_string()), entries[1].latest_release);
+        assert_eq!(Status::UpToDate, entries[2].status);
+    }
+
+    #[test]
+    fn reports_unknown_devices() {
+        let entries = report(&manufacturers(), vec![
+            device("Netgear", "R6100", "", "19.07.3"),
+            device("TP-Link", "Archer C8", "", "19.07.3"),
+            device("TP-Link", "Archer C7", "v6", "19.07.3")
+        ]);
+
+        assert!(entries.iter().all(|entry| entry.status == Status::UnknownDevice));
+    }
+
+    #[test]
+    fn reports_unsupported_devices_and_unknown_releases() {
+        let entries = report(&manufacturers(), vec![
+            device("TP-Link", "TL-WR703N", "", "15.05"),
+            device("TP-Link", "Archer C7", "v2", "")
+        ]);
+
+        assert_eq!(Status::Unsupported, entries[0].status);
+        assert_eq!(Status::UnknownRelease, entries[1].status);
+    }
+
+    #[test]
+    fn sums_up_every_status() {
+        let entries = report(&manufacturers(), vec![
+            device("TP-Link", "Archer C7", "v2", "18.06.8"),
+            device("TP-Link", "Archer C7", "v2", "19.07.3"),
+            device("TP-Link", "Archer C7", "v5", "19.07.3"),
+            device("TP-Link", "TL-WR703N", "", "15.05"),
+            device("TP-Link", "Archer C7", "v2", ""),
+            device("Netgear", "R6100", "", "19.07.3")
+        ]);
+
+        assert_eq!("6 device(s), 2 up to date, 1 behind, 1 unsupported, 1 unknown release, 1 unknown device", summary(&entries));
+    }
+}
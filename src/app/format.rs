@@ -0,0 +1,42 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// # `Format` represents how odin's reports are written out
+///
+/// `Table` - human readable table, the default
+/// `Csv` - comma separated values, with a header row
+/// `Json` - pretty printed JSON
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Table,
+    Csv,
+    Json
+}
+
+impl Format {
+    /// Names accepted on the command line
+    pub const NAMES: [&'static str; 3] = ["table", "csv", "json"];
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_lowercase().as_str() {
+            "table" => Ok(Format::Table),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format: {}", text))
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Table => write!(f, "table"),
+            Format::Csv => write!(f, "csv"),
+            Format::Json => write!(f, "json")
+        }
+    }
+}
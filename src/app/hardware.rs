@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use flate2::read::GzDecoder;
+use scraper::{Html, Selector};
+
+use super::data::{normalize, Hardware, Manufacturer};
+
+/// Magic bytes gzip files start with, the ToH dump is published gzipped
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+/// # `Record` represents technical data of a hardware revision, keyed by normalized field name
+///
+/// Field names of the ToH dump's header & labels of a device page's data entry both
+/// normalize to the same keys, ex: `flashmb` for "flashmb" & "Flash MB"
+#[derive(Debug, Default, PartialEq)]
+pub struct Record {
+    fields: HashMap<String, String>
+}
+
+/// Normalizes a field name, keeping lowercase letters & digits only, ex: "WLAN 2.4GHz" -> "wlan24ghz"
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+impl Record {
+    fn insert(&mut self, key: &str, value: &str) {
+        let value = value.split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        // the ToH writes unknown values as ¿ & missing ones as -
+        if !matches!(value.as_str(), "" | "-" | "¿" | "?") {
+            self.fields.entry(normalize_key(key)).or_insert(value);
+        }
+    }
+
+    fn get(&self, key: &str) -> &str {
+        self.fields.get(key).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Returns the smallest number in field `key`, ex: 8 for "8, 16" when variants differ
+    fn number(&self, key: &str) -> Option<u32> {
+        self.get(key)
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|number| number.parse().ok())
+            .min()
+    }
+
+    /// Returns technical data in this record, `None` when there's none
+    pub fn hardware(&self) -> Option<Hardware> {
+        let hardware = Hardware {
+            soc: self.get("cpu").to_string(),
+            flash_mb: self.number("flashmb"),
+            ram_mb: self.number("rammb"),
+            wlan_24ghz: self.get("wlan24ghz").to_string(),
+            wlan_50ghz: self.get("wlan50ghz").to_string(),
+            ethernet_100m_ports: self.number("ethernet100mports"),
+            ethernet_1g_ports: self.number("ethernetgbitports")
+        };
+
+        Some(hardware).filter(|hardware| *hardware != Hardware::default())
+    }
+
+    /// Returns whether this record is about model `model` of brand `brand`, see `normalize`
+    fn is_for(&self, brand: &str, model: &str) -> bool {
+        normalize(self.get("brand")) == normalize(brand) && normalize(self.get("model")) == normalize(model)
+    }
+}
+
+/// Parses the ToH dump, tab separated with a header row
+pub fn parse_dump(content: &str) -> Vec<Record> {
+    let mut lines = content.lines();
+    let header = match lines.next() {
+        Some(header) => header.split('\t').collect::<Vec<&str>>(),
+        None => return Vec::new()
+    };
+
+    lines.filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut record = Record::default();
+
+            for (key, value) in header.iter().zip(line.split('\t')) {
+                record.insert(key, value);
+            }
+
+            record
+        })
+        .collect()
+}
+
+/// Parses the data entry of a saved device page, labels followed by values,
+/// either as a definition list or two cell table rows
+pub fn parse_device_page(html_content: &str) -> Record {
+    let document = Html::parse_document(html_content);
+    let pair_selector = Selector::parse("dt, th, td, dd")
+        .expect("dt, th, td, dd is a valid selector");
+    let mut record = Record::default();
+    let mut label: Option<String> = None;
+
+    for element in document.select(&pair_selector) {
+        let text = element.text().collect::<Vec<&str>>().join(" ");
+
+        match element.value().name() {
+            "dt" | "th" => label = Some(text),
+            _ => if let Some(label) = label.take() {
+                record.insert(&label, &text);
+            }
+        }
+    }
+
+    record
+}
+
+/// Reads `path` as text, decompressing it when gzipped
+fn read_text(path: &Path) -> Result<String, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    let mut content = String::new();
+
+    if bytes.starts_with(GZIP_MAGIC) {
+        GzDecoder::new(bytes.as_slice()).read_to_string(&mut content)?;
+    }
+    else {
+        content = String::from_utf8(bytes)?;
+    }
+
+    Ok(content)
+}
+
+/// Loads records from `path`, one of
+///
+/// 1. The ToH dump, tab separated, gzipped or not
+/// 2. A saved device page
+/// 3. A directory of saved device pages, all `.html` files in it
+pub fn load_records(path: &str) -> Result<Vec<Record>, Box<dyn Error>> {
+    let expanded = shellexpand::tilde(path);
+    let path = Path::new(expanded.trim());
+
+    if path.is_dir() {
+        let mut records = Vec::new();
+
+        for entry in fs::read_dir(path)? {
+            let page = entry?.path();
+
+            if page.extension().is_some_and(|extension| extension == "html") {
+                records.push(parse_device_page(&read_text(&page)?));
+            }
+        }
+
+        return Ok(records);
+    }
+
+    let content = read_text(path)?;
+
+    if content.trim_start().starts_with('<') {
+        Ok(vec![parse_device_page(&content)])
+    }
+    else {
+        Ok(parse_dump(&content))
+    }
+}
+
+/// Attaches technical data of `records` to the matching hardware revisions of `manufacturers`,
+/// returns the number of revisions that got some
+///
+/// A record matches a revision by brand, model & hardware version, a record without
+/// a version matches revisions without one, or all revisions of a model with a single record
+pub fn attach(manufacturers: &mut [Manufacturer], records: &[Record]) -> usize {
+    let mut attached = 0;
+
+    for manufacturer in manufacturers.iter_mut() {
+        let brand = manufacturer.name().to_string();
+
+        for model in manufacturer.models_mut() {
+            let candidates = records.iter()
+                .filter(|record| record.is_for(&brand, model.name()))
+                .collect::<Vec<&Record>>();
+
+            if candidates.is_empty() {
+                continue;
+            }
+
+            for revision in model.revisions_mut() {
+                let record = candidates.iter()
+                    .find(|record| {
+                        let version = record.get("version");
+
+                        if version.is_empty() {
+                            revision.versions().is_empty() || candidates.len() == 1
+                        }
+                        else {
+                            revision.has_version(version)
+                        }
+                    });
+
+                if let Some(hardware) = record.and_then(|record| record.hardware()) {
+                    revision.set_hardware(hardware);
+                    attached += 1;
+                }
+            }
+        }
+    }
+
+    attached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::{find_manufacturer, ResourceClass};
+    use super::super::data::fixtures::manufacturers;
+
+    #[test]
+    fn attaches_dump_records_by_version() {
+        let records = parse_dump("pid\tbrand\tmodel\tversion\tcpu\tflashmb\trammb\twlan24ghz\twlan50ghz\tethernetgbitports\n\
+            1\tTP-Link\tArcher C7\tv2\tQualcomm Atheros QCA9558\t16\t128\tb/g/n\ta/n/ac\t5\n\
+            2\tTP-Link\tArcher C7\tv1\tQualcomm Atheros QCA9558\t8\t128\tb/g/n\ta/n\t5\n\
+            3\tTP-Link\tTL-WR841N\t\t¿\t4\t32\tb/g/n\t-\t-\n");
+
+        let mut manufacturers = manufacturers();
+        assert_eq!(3, attach(&mut manufacturers, &records));
+
+        let tp_link = find_manufacturer(&manufacturers, "TP-Link").unwrap();
+        let (_, revision) = tp_link.find_revision("archer c7", Some("v3")).unwrap();
+        let hardware = revision.hardware().unwrap();
+        assert_eq!("Qualcomm Atheros QCA9558", hardware.soc);
+        assert_eq!(Some(ResourceClass::Sufficient), hardware.resource_class());
+        assert_eq!("2.4 GHz b/g/n, 5 GHz a/n/ac", hardware.radios());
+
+        let (_, revision) = tp_link.find_revision("archer c7", Some("v1")).unwrap();
+        assert_eq!(Some(ResourceClass::EightSixtyFour), revision.hardware().unwrap().resource_class());
+
+        let (_, revision) = tp_link.find_revision("tl-wr841n", None).unwrap();
+        let hardware = revision.hardware().unwrap();
+        assert_eq!(Some(ResourceClass::FourThirtyTwo), hardware.resource_class());
+        assert!(hardware.soc.is_empty() && hardware.wlan_50ghz.is_empty());
+    }
+
+    #[test]
+    fn scrapes_device_page_data_entry() {
+        let record = parse_device_page(r#"
+            <div class="dataplugin_entry toh"><dl>
+                <dt class="brand">Brand<span class="sep">: </span></dt><dd class="brand"><a href="/toh/tp-link">TP-Link</a></dd>
+                <dt class="model">Model<span class="sep">: </span></dt><dd class="model">Archer C7</dd>
+                <dt class="version">Version<span class="sep">: </span></dt><dd class="version">v2</dd>
+                <dt class="flashmb">Flash MB<span class="sep">: </span></dt><dd class="flashmb">16</dd>
+                <dt class="rammb">RAM MB<span class="sep">: </span></dt><dd class="rammb">128</dd>
+                <dt class="wlan50ghz">WLAN 5.0GHz<span class="sep">: </span></dt><dd class="wlan50ghz">a/n/ac</dd>
+            </dl></div>
+        "#);
+
+        let mut manufacturers = manufacturers();
+        assert_eq!(1, attach(&mut manufacturers, &[record]));
+
+        let tp_link = find_manufacturer(&manufacturers, "TP-Link").unwrap();
+        let (_, revision) = tp_link.find_revision("archer c7", Some("v2")).unwrap();
+        assert_eq!("16/128", revision.hardware().unwrap().resources());
+        assert_eq!("5 GHz a/n/ac", revision.hardware().unwrap().radios());
+        assert_eq!(None, revision.hardware().unwrap().ethernet_ports());
+    }
+}
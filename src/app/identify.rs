@@ -0,0 +1,216 @@
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use serde::Deserialize;
+
+use super::data::{self, Manufacturer, Model, Revision};
+
+/// # `Board` represents the output of `ubus call system board` on a router
+///
+/// Only fields used for identification are kept
+///
+/// ## Example
+///
+/// {
+///     "model": "TP-Link Archer C7 v5",
+///     "board_name": "tplink,archer-c7-v5",
+///     "release": { "version": "19.07.3", "target": "ath79/generic" }
+/// }
+#[derive(Deserialize, Debug)]
+pub struct Board {
+    model: String,
+    #[serde(default)]
+    board_name: String,
+    #[serde(default)]
+    release: BoardRelease
+}
+
+/// OpenWrt release installed on a router, as reported by `ubus call system board`
+#[derive(Deserialize, Debug, Default)]
+pub struct BoardRelease {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    target: String
+}
+
+impl Board {
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn release(&self) -> &str {
+        &self.release.version
+    }
+
+    pub fn target(&self) -> &str {
+        &self.release.target
+    }
+}
+
+/// # `Identification` represents the catalog entry a board was matched to
+///
+/// `manufacturer` - brand of the board
+/// `model` - model of the board
+/// `revision` - hardware revision of the board, the latest one when its version isn't known
+/// `version` - hardware version of the board, `None` if the catalog doesn't know of it
+pub struct Identification<'m> {
+    pub manufacturer: &'m Manufacturer,
+    pub model: &'m Model,
+    pub revision: &'m Revision,
+    pub version: Option<String>
+}
+
+/// Loads board details from `file`, or from stdin when `file` is `-`
+pub fn load_board(file: &str) -> Result<Board, Box<dyn Error>> {
+    let content = if file == "-" {
+        let mut content = String::new();
+
+        io::stdin().read_to_string(&mut content)?;
+
+        content
+    }
+    else {
+        fs::read_to_string(shellexpand::tilde(file).trim())?
+    };
+
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Keeps only letters & digits of a normalized `name`, ex: "tp-link" -> "tplink"
+fn squash(name: &str) -> String {
+    data::normalize(name)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Returns what's left of `text` after `prefix`, if `text` starts with it on a word boundary
+fn strip_word_prefix<'t>(text: &'t str, prefix: &str) -> Option<&'t str> {
+    if prefix.is_empty() {
+        return None;
+    }
+
+    let rest = text.strip_prefix(prefix)?;
+
+    match rest.chars().next() {
+        Some(c) if c.is_alphanumeric() => None,
+        _ => Some(rest)
+    }
+}
+
+/// Finds the brand of `board`, by its model name or else by the vendor part of its board name
+fn find_manufacturer<'m>(manufacturers: &'m [Manufacturer], board: &Board) -> Option<(&'m Manufacturer, String)> {
+    let model = data::normalize(&board.model);
+
+    let by_model = manufacturers.iter()
+        .filter_map(|manufacturer| strip_word_prefix(&model, &data::normalize(manufacturer.name()))
+            .map(|rest| (manufacturer, rest.trim().to_string())))
+        .max_by_key(|(manufacturer, _)| manufacturer.name().len());
+
+    if by_model.is_some() {
+        return by_model;
+    }
+
+    // board names look like `vendor,device`, ex: tplink,archer-c7-v5
+    let vendor = board.board_name
+        .split(',')
+        .next()
+        .map(squash)
+        .unwrap_or_default();
+
+    manufacturers.iter()
+        .find(|manufacturer| !vendor.is_empty() && squash(manufacturer.name()) == vendor)
+        .map(|manufacturer| {
+            // model names usually repeat the brand, keep whatever follows it
+            let rest = model.split_once(' ')
+                .filter(|(brand, _)| squash(brand) == vendor)
+                .map(|(_, rest)| rest.to_string())
+                .unwrap_or(model);
+
+            (manufacturer, rest)
+        })
+}
+
+/// Matches `board` to a brand, model & hardware version in `manufacturers`
+pub fn identify<'m>(manufacturers: &'m [Manufacturer], board: &Board) -> Option<Identification<'m>> {
+    let (manufacturer, rest) = find_manufacturer(manufacturers, board)?;
+
+    // longest model name that `rest` starts with, ex: "archer c7" for "archer c7 v5"
+    let (name, rest) = manufacturer.models()
+        .iter()
+        .filter_map(|model| strip_word_prefix(&rest, &data::normalize(model.name()))
+            .map(|rest| (model.name().as_str(), rest)))
+        .max_by_key(|(name, _)| name.len())?;
+
+    // hardware version is whatever is left, ex: "v5" or "/nd v9"
+    let version = rest.split_whitespace()
+        .last()
+        .map(|version| version.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|version| !version.is_empty());
+
+    match version.as_deref().and_then(|version| manufacturer.find_revision(name, Some(version))) {
+        Some((model, revision)) => Some(Identification { manufacturer, model, revision, version }),
+        None => manufacturer.find_revision(name, None)
+            .map(|(model, revision)| Identification { manufacturer, model, revision, version: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::manufacturers;
+
+    fn board(json: &str) -> Board {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn identifies_board_by_model() {
+        let manufacturers = manufacturers();
+        let board = board(r#"{
+            "model": "TP-Link Archer C7 v5",
+            "board_name": "tplink,archer-c7-v5",
+            "release": { "version": "18.06.8", "target": "ath79/generic" }
+        }"#);
+
+        let identification = identify(&manufacturers, &board);
+        assert!(identification.is_some(), "Expected board to be identified");
+
+        let identification = identification.unwrap();
+        assert_eq!("TP-Link", identification.manufacturer.name());
+        assert_eq!("Archer C7", identification.model.name());
+        assert_eq!(Some("v5".to_string()), identification.version);
+        assert_eq!("19.07.3", identification.revision.openwrt_version().version());
+        assert_eq!("18.06.8", board.release());
+    }
+
+    #[test]
+    fn identifies_board_with_model_variants() {
+        let manufacturers = manufacturers();
+
+        let identification = identify(&manufacturers, &board(r#"{ "model": "TP-Link TL-WR841N/ND v9" }"#))
+            .unwrap();
+        assert_eq!("TL-WR841N", identification.model.name());
+        assert_eq!(Some("v9".to_string()), identification.version);
+
+        let identification = identify(&manufacturers, &board(r#"{ "model": "Netgear Nighthawk X4S R7800" }"#));
+        assert!(identification.is_none(), "Expected board not to be identified");
+
+        let identification = identify(&manufacturers, &board(r#"{ "model": "Netgear R7800" }"#))
+            .unwrap();
+        assert_eq!("R7800", identification.model.name());
+        assert_eq!(None, identification.version);
+    }
+
+    #[test]
+    fn identifies_board_by_board_name_vendor() {
+        let manufacturers = manufacturers();
+        let board = board(r#"{ "model": "TPLINK Archer C7 v1", "board_name": "tplink,archer-c7-v1" }"#);
+
+        let identification = identify(&manufacturers, &board)
+            .unwrap();
+        assert_eq!("TP-Link", identification.manufacturer.name());
+        assert_eq!("17.01.7", identification.revision.openwrt_version().version());
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use colored::*;
+use serde::{Serialize, Deserialize};
+
+use super::data::{self, Model, ReleaseNumber, Revision};
+
+const DOWNLOADS_PAGE: &str = "https://downloads.openwrt.org";
+
+/// # `Profiles` represents a `profiles.json` of OpenWrt's firmware selector, one per target
+///
+/// `version_number` - OpenWrt release the images were built for, ex: 19.07.3
+/// `target` - target the images were built for, ex: ath79/generic
+/// `profiles` - device profiles of this target, by profile id
+#[derive(Deserialize, Debug)]
+pub struct Profiles {
+    version_number: String,
+    target: String,
+    profiles: HashMap<String, Profile>
+}
+
+/// A device profile, with the devices it's meant for & the images built for them
+#[derive(Deserialize, Debug)]
+struct Profile {
+    #[serde(default)]
+    titles: Vec<Title>,
+    #[serde(default)]
+    images: Vec<ProfileImage>
+}
+
+/// A device a profile is meant for, either split up into vendor, model & variant,
+/// or as a single title
+#[derive(Deserialize, Debug, Default)]
+struct Title {
+    #[serde(default)]
+    vendor: String,
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    variant: String,
+    #[serde(default)]
+    title: String
+}
+
+impl Title {
+    /// Returns the full title, ex: TP-Link Archer C7 v2
+    fn describe(&self) -> String {
+        if !self.title.is_empty() {
+            return self.title.clone();
+        }
+
+        [&self.vendor, &self.model, &self.variant].iter()
+            .filter(|val| !val.is_empty())
+            .map(|val| val.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    /// Returns whether this title is for `model` of `brand`, one of `versions` if given
+    fn matches(&self, brand: &str, model: &str, versions: &[String]) -> bool {
+        let squash = |name: &str| data::normalize(name)
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>();
+        let is_version = |variant: &str| versions.is_empty() ||
+            versions.iter().any(|version| data::normalize_version(version) == data::normalize_version(variant));
+
+        if !self.title.is_empty() {
+            let title = data::normalize(&self.title);
+            let prefix = data::normalize(&format!("{} {}", brand, model));
+
+            return match title.strip_prefix(&prefix) {
+                Some("") => versions.is_empty(),
+                Some(rest) if rest.starts_with(' ') => is_version(rest.trim()),
+                _ => false
+            };
+        }
+
+        squash(&self.vendor) == squash(brand) &&
+            data::normalize(&self.model) == data::normalize(model) &&
+            is_version(&self.variant)
+    }
+}
+
+/// An image of a profile, as listed in `profiles.json`
+#[derive(Deserialize, Debug)]
+struct ProfileImage {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    filesystem: String,
+    #[serde(default)]
+    sha256: String
+}
+
+/// # `Image` represents a firmware image for a catalog model
+///
+/// `profile` - id of the firmware selector profile the image was built for
+/// `title` - device the profile is meant for, ex: TP-Link Archer C7 v2
+/// `release` - OpenWrt release of the image
+/// `kind` - kind of image, ex: sysupgrade, factory
+/// `url` - download path of the image
+#[derive(Serialize, Debug)]
+pub struct Image {
+    profile: String,
+    title: String,
+    release: String,
+    target: String,
+    kind: String,
+    filesystem: String,
+    name: String,
+    url: String,
+    sha256: String
+}
+
+impl Profiles {
+    /// Returns the downloads directory of this target, ex:
+    /// https://downloads.openwrt.org/releases/19.07.3/targets/ath79/generic
+    fn downloads_dir(&self) -> String {
+        if self.version_number.to_lowercase() == "snapshot" {
+            format!("{}/snapshots/targets/{}", DOWNLOADS_PAGE, self.target)
+        }
+        else {
+            format!("{}/releases/{}/targets/{}", DOWNLOADS_PAGE, self.version_number, self.target)
+        }
+    }
+}
+
+/// Lists `profiles.json` files at `path`, a file or a directory of them
+fn profile_files(path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = fs::read_dir(path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|extension| extension == "json").unwrap_or(false))
+        .collect::<Vec<PathBuf>>();
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Loads `profiles.json` files from `paths`, each one a file or a directory of them
+pub fn load_profiles(paths: &[&str]) -> Result<Vec<Profiles>, Box<dyn Error>> {
+    let mut profiles = Vec::new();
+
+    for path in paths {
+        let path = shellexpand::tilde(path);
+
+        for file in profile_files(Path::new(path.trim()))? {
+            let content = fs::read_to_string(&file)
+                .map_err(|err| format!("{}: {}", file.display(), err))?;
+            let content = serde_json::from_str(&content)
+                .map_err(|err| format!("{}: {}", file.display(), err))?;
+
+            profiles.push(content);
+        }
+    }
+
+    Ok(profiles)
+}
+
+/// Finds images in `profiles` for `model` of `brand`, each hardware revision's images built
+/// for the revision's own supported release
+///
+/// Only the given `version` is looked for when there's one, else all hardware versions of `model`
+pub fn find_images(profiles: &[Profiles], brand: &str, model: &Model, version: Option<&str>) -> Vec<Image> {
+    let revisions = match version {
+        Some(version) => model.find_revision(Some(version))
+            .into_iter()
+            .collect::<Vec<&Revision>>(),
+        None => model.revisions()
+            .iter()
+            .collect()
+    };
+
+    revisions.into_iter()
+        .flat_map(|revision| {
+            let versions = match version {
+                Some(version) => vec![version.to_string()],
+                None => revision.versions().clone()
+            };
+
+            find_revision_images(profiles, brand, model.name(), &versions, revision)
+        })
+        .collect()
+}
+
+/// Finds images in `profiles` for hardware `versions` of `model` of `brand`, built for the
+/// supported release of `revision`
+fn find_revision_images(profiles: &[Profiles], brand: &str, model: &str, versions: &[String], revision: &Revision) -> Vec<Image> {
+    let branch = revision.openwrt_version()
+        .release()
+        .map(|release| release.branch());
+
+    profiles.iter()
+        .filter(|profiles| branch.is_none() || profiles.version_number
+            .parse::<ReleaseNumber>()
+            .map(|release| Some(release.branch()) == branch)
+            .unwrap_or(false))
+        .flat_map(|profiles| {
+            let mut ids = profiles.profiles.keys().collect::<Vec<&String>>();
+
+            ids.sort();
+            ids.into_iter()
+                .filter_map(|id| {
+                    let profile = &profiles.profiles[id];
+                    let title = profile.titles.iter()
+                        .find(|title| title.matches(brand, model, versions))?;
+
+                    Some(profile.images.iter()
+                        .map(|image| Image {
+                            profile: id.clone(),
+                            title: title.describe(),
+                            release: profiles.version_number.clone(),
+                            target: profiles.target.clone(),
+                            kind: image.kind.clone(),
+                            filesystem: image.filesystem.clone(),
+                            name: image.name.clone(),
+                            url: format!("{}/{}", profiles.downloads_dir(), image.name),
+                            sha256: image.sha256.clone()
+                        })
+                        .collect::<Vec<Image>>())
+                })
+                .flatten()
+                .collect::<Vec<Image>>()
+        })
+        .collect()
+}
+
+/// Prints `images` grouped by their profile
+pub fn print_table(images: &[Image]) {
+    let mut profile = "";
+
+    for image in images {
+        if image.profile != profile {
+            profile = &image.profile;
+
+            println!();
+            println!("{} {} ({}, {} {})", "Profile:".bold().bright_cyan(), image.profile, image.title, image.target, image.release);
+        }
+
+        println!("  {:<12}{}", image.kind.bold(), image.name);
+        println!("  {:<12}{}", "", image.url);
+        println!("  {:<12}{}", "", image.sha256);
+    }
+
+    println!();
+}
+
+/// Prints `images` as csv, with a header row
+pub fn print_csv(images: &[Image]) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_writer(io::stdout());
+
+    for image in images {
+        writer.serialize(image)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Prints `images` as pretty printed JSON
+pub fn print_json(images: &[Image]) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(images)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::{model, revision};
+
+    const PROFILES: &str = r#"{
+        "metadata_version": 1,
+        "target": "ath79/generic",
+        "version_code": "r11063-85e04e9f46",
+        "version_number": "19.07.3",
+        "profiles": {
+            "tplink_archer-c7-v2": {
+                "image_prefix": "openwrt-19.07.3-ath79-generic-tplink_archer-c7-v2",
+                "images": [
+                    {
+                        "filesystem": "squashfs",
+                        "name": "openwrt-19.07.3-ath79-generic-tplink_archer-c7-v2-squashfs-factory.bin",
+                        "sha256": "0cd8a8d8cd9bbd6e6f9dd8fe8a1ddc7a5bb31b8a3c3dc1f47a3d0e1c6b6b4a11",
+                        "type": "factory"
+                    },
+                    {
+                        "filesystem": "squashfs",
+                        "name": "openwrt-19.07.3-ath79-generic-tplink_archer-c7-v2-squashfs-sysupgrade.bin",
+                        "sha256": "6f0b4c1c8a6b1a3a9b1d3c7f1e3a5e7f9b0c2d4e6f8a0b2c4d6e8f0a2b4c6d8e",
+                        "type": "sysupgrade"
+                    }
+                ],
+                "supported_devices": ["tplink,archer-c7-v2"],
+                "titles": [{ "model": "Archer C7", "variant": "v2", "vendor": "TP-Link" }]
+            },
+            "tplink_archer-c7-v5": {
+                "images": [
+                    {
+                        "filesystem": "squashfs",
+                        "name": "openwrt-19.07.3-ath79-generic-tplink_archer-c7-v5-squashfs-sysupgrade.bin",
+                        "sha256": "9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b",
+                        "type": "sysupgrade"
+                    }
+                ],
+                "titles": [{ "title": "TP-Link Archer C7 v5" }]
+            },
+            "netgear_r6100": {
+                "images": [
+                    {
+                        "name": "openwrt-19.07.3-ath79-generic-netgear_r6100-squashfs-sysupgrade.bin",
+                        "type": "sysupgrade"
+                    }
+                ],
+                "titles": [{ "model": "R6100", "vendor": "NETGEAR" }]
+            }
+        }
+    }"#;
+
+    fn profiles() -> Vec<Profiles> {
+        vec![serde_json::from_str(PROFILES).unwrap()]
+    }
+
+    #[test]
+    fn finds_images_for_model_versions() {
+        let images = find_images(&profiles(), "TP-Link", &model("Archer C7", vec![revision(&["v2", "v5"], "19.07.2")]), None);
+        assert_eq!(3, images.len(), "Expected 3 images, but found {}", images.len());
+
+        let image = &images[1];
+        assert_eq!("tplink_archer-c7-v2", image.profile);
+        assert_eq!("TP-Link Archer C7 v2", image.title);
+        assert_eq!("sysupgrade", image.kind);
+        assert_eq!("https://downloads.openwrt.org/releases/19.07.3/targets/ath79/generic/openwrt-19.07.3-ath79-generic-tplink_archer-c7-v2-squashfs-sysupgrade.bin", image.url);
+        assert_eq!("6f0b4c1c8a6b1a3a9b1d3c7f1e3a5e7f9b0c2d4e6f8a0b2c4d6e8f0a2b4c6d8e", image.sha256);
+
+        assert_eq!("tplink_archer-c7-v5", images[2].profile);
+    }
+
+    #[test]
+    fn finds_images_for_given_version() {
+        let images = find_images(&profiles(), "tp-link", &model("Archer C7", vec![revision(&["v2", "v5"], "19.07.3")]), Some("V5"));
+        assert_eq!(1, images.len(), "Expected 1 image, but found {}", images.len());
+        assert_eq!("TP-Link Archer C7 v5", images[0].title);
+
+        let images = find_images(&profiles(), "Netgear", &model("R6100", vec![revision(&[], "19.07.3")]), None);
+        assert_eq!(1, images.len(), "Expected 1 image, but found {}", images.len());
+    }
+
+    #[test]
+    fn doesnt_find_images_for_other_releases() {
+        let images = find_images(&profiles(), "TP-Link", &model("Archer C7", vec![revision(&["v2"], "18.06.8")]), None);
+        assert!(images.is_empty(), "Expected no images, but found {}", images.len());
+
+        let model = model("Archer C7", vec![
+            revision(&["v2"], "18.06.8"),
+            revision(&["v5"], "19.07.3")
+        ]);
+        let images = find_images(&profiles(), "TP-Link", &model, None);
+        assert_eq!(1, images.len(), "Expected 1 image, but found {}", images.len());
+        assert_eq!("tplink_archer-c7-v5", images[0].profile);
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use reqwest::{blocking, Method, StatusCode, Url};
+
+use super::data::Manufacturer;
+
+/// # `LinkStatus` represents how a device page responded
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkStatus {
+    /// Page responded with a success status, after following redirects
+    Alive(u16),
+    /// Page doesn't exist, `404 Not Found` or `410 Gone`
+    Dead(u16),
+    /// Page couldn't be checked, ex: connection refused, server errors
+    Unreachable(String)
+}
+
+/// # `LinkCheck` represents a checked device page
+///
+/// `url` - device page, as in the catalog
+/// `checked_url` - URL that was requested, on the mirror when there's one
+/// `models` - models linking to the device page, ex: TP-Link Archer C7 v2
+/// `status` - how the device page responded
+#[derive(Debug)]
+pub struct LinkCheck {
+    pub url: String,
+    pub checked_url: String,
+    pub models: Vec<String>,
+    pub status: LinkStatus
+}
+
+/// Collects device pages of `manufacturers`, along with the models linking to each of them
+pub fn device_pages(manufacturers: &[Manufacturer]) -> BTreeMap<String, Vec<String>> {
+    let mut pages: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for manufacturer in manufacturers {
+        for model in manufacturer.models() {
+            for revision in model.revisions() {
+                if revision.device_page().is_empty() {
+                    continue;
+                }
+
+                let name = format!("{} {} {}", manufacturer.name(), model.name(), revision.versions_text());
+
+                pages.entry(revision.device_page().to_string())
+                    .or_default()
+                    .push(name.trim().to_string());
+            }
+        }
+    }
+
+    pages
+}
+
+/// Points `url` to `mirror`, keeping its path & query
+///
+/// ## Example
+///
+/// "https://openwrt.org/toh/tp-link/archer_c7" on "http://localhost:8000/openwrt/"
+/// -> "http://localhost:8000/openwrt/toh/tp-link/archer_c7"
+pub fn rebase(url: &str, mirror: &Url) -> Result<Url, Box<dyn Error>> {
+    let url = Url::parse(url)?;
+    let mut mirror = mirror.clone();
+
+    if !mirror.path().ends_with('/') {
+        mirror.set_path(&format!("{}/", mirror.path()));
+    }
+
+    let mut rebased = mirror.join(url.path().trim_start_matches('/'))?;
+
+    rebased.set_query(url.query());
+
+    Ok(rebased)
+}
+
+/// Requests `url`, with `HEAD` unless the server doesn't support it
+fn check_url(client: &blocking::Client, url: &str) -> LinkStatus {
+    let request = |method: Method| client.request(method, url)
+        .send()
+        .map(|response| response.status());
+    let status = match request(Method::HEAD) {
+        Ok(StatusCode::METHOD_NOT_ALLOWED) | Ok(StatusCode::NOT_IMPLEMENTED) => request(Method::GET),
+        status => status
+    };
+
+    match status {
+        Ok(status) if status.is_success() => LinkStatus::Alive(status.as_u16()),
+        Ok(status) if status == StatusCode::NOT_FOUND || status == StatusCode::GONE => LinkStatus::Dead(status.as_u16()),
+        Ok(status) => LinkStatus::Unreachable(format!("HTTP {}", status)),
+        Err(err) => LinkStatus::Unreachable(err.to_string())
+    }
+}
+
+/// Checks device `pages`, on `mirror` when given, with `jobs` requests at a time
+///
+/// Results are in the order of `pages`
+pub fn check(pages: BTreeMap<String, Vec<String>>, mirror: Option<&Url>, jobs: usize, timeout: Duration) -> Result<Vec<LinkCheck>, Box<dyn Error>> {
+    let client = blocking::Client::builder()
+        .timeout(timeout)
+        .build()?;
+    let mut queue = Vec::new();
+
+    for (i, (url, models)) in pages.into_iter().enumerate() {
+        let checked_url = match mirror {
+            Some(mirror) => rebase(&url, mirror)?.to_string(),
+            None => url.clone()
+        };
+
+        queue.push((i, LinkCheck { url, checked_url, models, status: LinkStatus::Unreachable(String::new()) }));
+    }
+
+    let count = queue.len();
+    let queue = Arc::new(Mutex::new(queue));
+    let (sender, receiver) = mpsc::channel();
+
+    for _ in 0..jobs.max(1).min(count.max(1)) {
+        let queue = Arc::clone(&queue);
+        let sender = sender.clone();
+        let client = client.clone();
+
+        thread::spawn(move || loop {
+            let next = match queue.lock() {
+                Ok(mut queue) => queue.pop(),
+                Err(_) => None
+            };
+            let (i, mut link) = match next {
+                Some(next) => next,
+                None => break
+            };
+
+            link.status = check_url(&client, &link.checked_url);
+
+            if sender.send((i, link)).is_err() {
+                break;
+            }
+        });
+    }
+
+    drop(sender);
+
+    let mut links = receiver.iter()
+        .collect::<Vec<(usize, LinkCheck)>>();
+
+    links.sort_by_key(|(i, _)| *i);
+
+    Ok(links.into_iter()
+        .map(|(_, link)| link)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiny_http::{Response, Server};
+    use super::super::data::fixtures::manufacturers;
+
+    #[test]
+    fn rebases_links_on_mirror() {
+        let mirror = Url::parse("http://localhost:8000/openwrt").unwrap();
+
+        assert_eq!(
+            "http://localhost:8000/openwrt/toh/tp-link/archer_c7?rev=1",
+            rebase("https://openwrt.org/toh/tp-link/archer_c7?rev=1", &mirror).unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn reports_dead_device_pages() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let status = if request.url() == "/toh/tp-link/archer_c7" { 200 } else { 404 };
+
+                let _ = request.respond(Response::empty(status));
+            }
+        });
+
+        let manufacturers = manufacturers();
+        let pages = device_pages(&manufacturers[1..]);
+        assert_eq!(4, pages.len(), "Expected 4 device pages, but found {}", pages.len());
+
+        let mirror = Url::parse(&format!("http://{}", addr)).unwrap();
+        let links = check(pages, Some(&mirror), 2, Duration::from_secs(5)).unwrap();
+        let statuses = links.iter()
+            .map(|link| (link.url.as_str(), link.status.clone()))
+            .collect::<Vec<(&str, LinkStatus)>>();
+
+        assert_eq!(vec![
+            ("https://openwrt.org/toh/tp-link/archer_c7", LinkStatus::Alive(200)),
+            ("https://openwrt.org/toh/tp-link/archer_c7_v1", LinkStatus::Dead(404)),
+            ("https://openwrt.org/toh/tp-link/tl-wr703n", LinkStatus::Dead(404)),
+            ("https://openwrt.org/toh/tp-link/tl-wr841nd", LinkStatus::Dead(404))
+        ], statuses);
+        assert_eq!(vec!["TP-Link Archer C7 v2, v3", "TP-Link Archer C7 v5", "TP-Link Archer C7 AC1750 v4"], links[0].models);
+    }
+}
@@ -1,165 +1,645 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::fs;
-use scraper::{Html, Selector};
-use reqwest::{self, blocking, Url};
-use shellexpand;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use scraper::{ElementRef, Html, Node, Selector};
+use reqwest::{blocking, Url};
 
-use super::{OPENWRT_ROOT_PAGE, ALL_DEVICES_PAGE, ODIN_DEVICE_PAGE, Model, Manufacturer, Version};
-use super::Log;
+mod cache;
+mod catalog;
+mod download;
+mod link;
+mod snapshot;
+mod stream;
+mod warning;
 
-/// Loads html content from OpenWrt's `supported devices` page
-fn load_html_content_from_http() -> Result<Option<String>, Box<dyn Error>> {
+pub use download::{interrupt, set_max_body_mb, Interrupted, Interruptible};
+pub use link::resolve;
+pub use warning::{Problem, RowWarning, EXPECTED_CELLS};
+
+use cache::{Cache, Meta};
+use stream::Item;
+
+use super::data;
+use super::{ALL_DEVICES_PAGE, ODIN_CACHED_PAGE, ODIN_DEVICE_PAGE, Model, Manufacturer, Revision, Version};
+
+/// Source of brands parsed from the bundled snapshot, followed by its date
+const BUNDLED_SNAPSHOT: &str = "bundled snapshot from";
+
+/// Loads html content from OpenWrt's `supported devices` page, along with the URL it
+/// was finally loaded from
+///
+/// The body is streamed, with progress on stderr when it's a terminal, & rejected once it
+/// grows beyond `download::max_body_bytes`, Ctrl-C aborts it, see `Interruptible`
+fn load_html_content_from_http(interruptible: &Interruptible) -> Result<Option<(String, Url)>, Box<dyn Error>> {
     let client = blocking::Client::new();
     let response = client.get(Url::parse(ALL_DEVICES_PAGE)?)
         .send()?;
     let status_code = response.status();
-    let response_content = response.text()?;
+    let url = response.url().clone();
+    let total = response.content_length();
+    let content_type = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(String::from);
+    let body = download::read_body(response, url.as_str(), total, download::max_body_bytes(), interruptible)?;
+    let response_content = download::decode(&body, content_type.as_deref());
+
+    debug!("{} answered {}", url, status_code);
 
     if status_code != 200 {
-        Log::print_error(format!("Error: {}", response_content));
-    
+        error!("{} answered {}: {}", url, status_code, download::summarize(&response_content));
+
         return Ok(None);
     }
 
-    Ok(Some(response_content))
+    Ok(Some((response_content, url)))
 }
 
-fn load_html_content_from_file(file: &str) -> Option<String> {
+/// URL of the devices page, which links of a saved copy are relative to
+fn default_base() -> Url {
+    Url::parse(ALL_DEVICES_PAGE)
+        .expect("devices page URL is valid")
+}
+
+fn open_html_content_file(file: &str) -> Option<BufReader<File>> {
     // we don't care about file errors
-    match fs::read_to_string(shellexpand::tilde(file).trim()) {
-        Ok(html_content) => Some(html_content),
+    match File::open(shellexpand::tilde(file).trim()) {
+        Ok(file) => Some(BufReader::new(file)),
         Err(_) => None
     }
 }
 
-/// Loads all brand details by fetching html content from OpenWrt's `supported` page
-/// This can be used when network connection is fast & there aren't any network issues
-pub fn load_manufacturers(file: Option<&str>) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+/// # `Parsed` represents the outcome of parsing the devices table
+///
+/// `manufacturers` - all brands, `None` when no rows were found
+/// `rows` - number of data rows parsed
+/// `warnings` - problems found in rows, in row order
+/// `source` - where the brands were loaded from, ex: a file, a URL or the bundled snapshot
+#[derive(Debug)]
+pub struct Parsed {
+    pub manufacturers: Option<Vec<Manufacturer>>,
+    pub rows: usize,
+    pub warnings: Vec<RowWarning>,
+    pub source: String
+}
+
+impl Parsed {
+    /// Wraps brands of a JSON catalog, which has no rows to warn about,
+    /// each hardware revision counts as a row
+    fn from_catalog(manufacturers: Vec<Manufacturer>) -> Self {
+        let rows = manufacturers.iter()
+            .flat_map(|manufacturer| manufacturer.models())
+            .map(|model| model.revisions().len())
+            .sum();
+
+        Self {
+            manufacturers: Some(manufacturers).filter(|manufacturers| !manufacturers.is_empty()),
+            rows,
+            warnings: Vec::new(),
+            source: String::new()
+        }
+    }
+
+    /// Notes the brands were loaded from `source`
+    fn with_source(mut self, source: String) -> Self {
+        self.source = source;
+
+        self
+    }
+}
+
+/// Parses all brand details from a file opened as `reader`, either a saved devices page or
+/// a JSON catalog, told apart by content
+///
+/// JSON catalogs have no rows to warn about, each hardware revision counts as a row
+fn parse_file(mut reader: BufReader<File>) -> Result<Parsed, Box<dyn Error>> {
+    if !catalog::is_json(&mut reader)? {
+        return parse_from_reader(reader, default_base());
+    }
+
+    Ok(Parsed::from_catalog(catalog::parse_json(reader)?))
+}
+
+/// Parses brands of the catalog bundled into the binary, calling out its date,
+/// as it may be well out of date
+fn parse_snapshot(snapshot: snapshot::Snapshot) -> Parsed {
+    warn!("Using the bundled snapshot from {}, it may be out of date!", snapshot.date);
+
+    Parsed::from_catalog(snapshot.manufacturers)
+        .with_source(format!("{} {}", BUNDLED_SNAPSHOT, snapshot.date))
+}
+
+/// Fetches the devices page from the web & parses it, the page is cached once it passes
+/// `cache::validate` against the last good one
+///
+/// Ctrl-C aborts before anything is cached, a cache being written is always finished
+///
+/// Returns `None` when the server didn't answer with the page
+fn fetch_manufacturers(cache: &Cache) -> Result<Option<Parsed>, Box<dyn Error>> {
+    let interruptible = Interruptible::begin();
+    let (html_content, url) = match load_html_content_from_http(&interruptible)? {
+        Some(page) => page,
+        None => return Ok(None)
+    };
+    let parsed = parse_from(&html_content, url.clone())
+        .with_source(url.to_string());
+    let previous = cache.meta();
+    let meta = Meta::new(url.as_str(), &html_content, parsed.rows);
+
+    cache::validate(&html_content, &meta, previous.as_ref())
+        .map_err(|err| format!("Rejected page from {}: {}", url, err))?;
+
+    debug!("Page has {} row(s), sha256 {}", meta.rows, meta.sha256);
+
+    if previous.is_some_and(|previous| previous.sha256 == meta.sha256) {
+        debug!("Page didn't change since it was last fetched");
+    }
+
+    interruptible.check()?;
+
+    if let Err(err) = cache.store(&html_content, &meta) {
+        warn!("Couldn't cache page from web: {}", err);
+    }
+
+    Ok(Some(parsed))
+}
+
+/// Parses `html_content` of the last good page fetched from the web, described by `meta`
+fn parse_cached(html_content: &str, meta: &Meta) -> Parsed {
+    let base = Url::parse(&meta.url).unwrap_or_else(|_| default_base());
+
+    parse_from(html_content, base).with_source(ODIN_CACHED_PAGE.to_string())
+}
+
+/// Parses the last good page fetched from the web, else the catalog bundled into the binary,
+/// for when the web failed with `err`, which is returned when there's neither
+fn parse_fallback(cache: &Cache, err: Option<Box<dyn Error>>) -> Result<Option<Parsed>, Box<dyn Error>> {
+    let cached = cache.load();
+    let snapshot = match cached {
+        Some(_) => None,
+        None => snapshot::load()?
+    };
+
+    if cached.is_none() && snapshot.is_none() {
+        return err.map_or(Ok(None), Err);
+    }
+
+    if let Some(err) = err {
+        error!("Error loading content from web: {}", err);
+    }
+
+    match cached {
+        Some((html_content, meta)) => {
+            warn!("Using the page cached {}, it may be out of date!", meta.age());
+
+            Ok(Some(parse_cached(&html_content, &meta)))
+        },
+        None => Ok(snapshot.map(parse_snapshot))
+    }
+}
+
+/// Parses all brand details from user given `file`, the one in odin home or the web,
+/// along with warnings about rows that couldn't be parsed completely
+///
+/// `file` may be a saved devices page or a JSON catalog, see `parse_file`
+///
+/// Pages fetched from the web are validated before they're accepted & cached. When one is
+/// rejected or couldn't be loaded, the last good page is used, then the catalog bundled into
+/// the binary, when built with the `bundled-snapshot` feature
+///
+/// Returns `None` when the page couldn't be loaded from the web & there's nothing to fall back to
+pub fn parse_manufacturers(file: Option<&str>) -> Result<Option<Parsed>, Box<dyn Error>> {
     let file = file.unwrap_or(ODIN_DEVICE_PAGE);
 
     // try loading content from user given file or one from odin home (if available)
-    let html_content = if let Some(html_content) = load_html_content_from_file(file) {
-        println!("Loaded content from file: {0}", file);
-        
-        html_content
+    if let Some(reader) = open_html_content_file(file) {
+        info!("Loaded content from file: {0}", file);
+
+        return parse_file(reader)
+            .map(|parsed| Some(parsed.with_source(file.to_string())))
+            .map_err(|err| format!("{}: {}", file, err).into());
     }
+
     // do it the hard way, http!
-    else {
-        println!("Loading content from web");
+    info!("Loading content from web");
+
+    let cache = Cache::new(ODIN_CACHED_PAGE);
+
+    match fetch_manufacturers(&cache) {
+        Ok(Some(parsed)) => Ok(Some(parsed)),
+        Err(err) if err.is::<Interrupted>() => Err(err),
+        Ok(None) => parse_fallback(&cache, None),
+        Err(err) => parse_fallback(&cache, Some(err))
+    }
+}
+
+/// Loads all brand details by fetching html content from OpenWrt's `supported` page
+/// This can be used when network connection is fast & there aren't any network issues
+///
+/// Rows that couldn't be parsed completely are counted at debug & listed at trace level,
+/// `odin lint` gives a full report
+pub fn load_manufacturers(file: Option<&str>) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+    Ok(load_manufacturers_with_source(file)?.map(|(manufacturers, _)| manufacturers))
+}
+
+/// All brands along with where they were loaded from, see `Parsed`
+pub type Sourced = (Vec<Manufacturer>, String);
+
+/// Whether brands loaded from `source` came from a fallback, the cached page or the bundled
+/// snapshot, rather than the page the user asked for, so they may be out of date
+pub fn is_fallback(source: &str) -> bool {
+    source == ODIN_CACHED_PAGE || source.starts_with(BUNDLED_SNAPSHOT)
+}
+
+/// Loads all brand details, see `load_manufacturers`, along with where they were loaded from,
+/// ex: a file, the web, the cached page or the bundled snapshot
+pub fn load_manufacturers_with_source(file: Option<&str>) -> Result<Option<Sourced>, Box<dyn Error>> {
+    let parsed = match parse_manufacturers(file)? {
+        Some(parsed) => parsed,
+        None => return Ok(None)
+    };
+
+    debug!("Parsed {} row(s) from {} with {} problem(s)", parsed.rows, parsed.source, parsed.warnings.len());
+
+    for warning in &parsed.warnings {
+        trace!("{}", warning);
+    }
+
+    let source = parsed.source;
+
+    Ok(parsed.manufacturers.map(|manufacturers| (manufacturers, source)))
+}
+
+/// Loads all brand details from user given `file` or the one in odin home, never from the web,
+/// `file` may be a saved devices page or a JSON catalog. The last good page fetched from the web
+/// comes next, the bundled snapshot is the last resort
+/// Meant for places where a network round trip isn't acceptable, like shell completions
+pub fn load_manufacturers_offline(file: Option<&str>) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+    let reader = file.and_then(open_html_content_file)
+        .or_else(|| open_html_content_file(ODIN_DEVICE_PAGE));
+
+    if let Some(reader) = reader {
+        return Ok(parse_file(reader)?.manufacturers);
+    }
+
+    if let Some((html_content, meta)) = Cache::new(ODIN_CACHED_PAGE).load() {
+        return Ok(parse_cached(&html_content, &meta).manufacturers);
+    }
+
+    Ok(snapshot::load()?.map(|snapshot| snapshot.manufacturers))
+}
+
+/// First anchor of a table cell
+#[derive(Debug, Default, PartialEq)]
+pub struct Anchor {
+    text: Option<String>,
+    href: Option<String>
+}
 
-        match load_html_content_from_http()? {
-            Some(html_content) => html_content,
+/// A cell of the devices table, with all of its text & its first anchor
+#[derive(Debug, Default, PartialEq)]
+pub struct Cell {
+    text: Option<String>,
+    anchor: Option<Anchor>
+}
+
+/// Turns all whitespace in `text`, line breaks included, into spaces
+fn flatten_whitespace(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect()
+}
+
+/// Collapses runs of whitespace in each line of `text` into single spaces & drops empty lines,
+/// `None` when nothing's left
+///
+/// Lines come from line breaks & blocks within a cell, see `stream::RowSink`
+fn clean_text(text: Option<String>) -> Option<String> {
+    text.map(|text| text.lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n"))
+        .filter(|text| !text.is_empty())
+}
+
+impl Cell {
+    /// Returns this cell with whitespace of its texts collapsed, see `clean_text`
+    fn cleaned(self) -> Self {
+        Self {
+            text: clean_text(self.text),
+            anchor: self.anchor.map(|anchor| Anchor { text: clean_text(anchor.text), href: anchor.href })
+        }
+    }
+
+    /// Returns all text of this cell on a single line
+    fn line(&self) -> Option<String> {
+        self.text
+            .as_ref()
+            .map(|text| text.replace('\n', " "))
+    }
+
+    /// Returns all text of this cell, for reporting problems with it
+    fn raw(&self) -> String {
+        self.line()
+            .unwrap_or_default()
+    }
+}
+
+/// A data row of the devices table, with all of its `td` cells
+#[derive(Debug, Default, PartialEq)]
+pub struct Row {
+    cells: Vec<Cell>
+}
+
+/// Builds models out of rows & files them under their manufacturer,
+/// noting problems with rows along the way
+///
+/// `base` - URL links are resolved against, the page's own unless it has a `<base>`
+struct Collector {
+    base: Url,
+    manufacturer_models_map: HashMap<String, Vec<Model>>,
+    rows: usize,
+    warnings: Vec<RowWarning>
+}
+
+impl Collector {
+    fn new(base: Url) -> Self {
+        Self { base, manufacturer_models_map: HashMap::new(), rows: 0, warnings: Vec::new() }
+    }
+
+    fn add(&mut self, item: Item) {
+        match item {
+            Item::Base(href) => {
+                if let Some(base) = link::resolve(&self.base, &href) {
+                    self.base = base;
+                }
+            },
+            Item::Row(row) => self.add_row(row)
+        }
+    }
+
+    /// Notes `problem` with the current row
+    fn warn(&mut self, problem: Problem, raw: String) {
+        self.warnings.push(RowWarning { row: self.rows, problem, raw });
+    }
+
+    /// Returns text of `cell` on a single line, warning when it's empty
+    fn text(&mut self, cell: Cell, field: &'static str) -> Option<String> {
+        if cell.text.is_none() {
+            self.warn(Problem::Missing(field), cell.raw());
+        }
+
+        cell.line()
+    }
+
+    /// Returns text & resolved URL of the anchor of `cell`, warning when either is missing
+    fn link(&mut self, cell: Cell, field: &'static str) -> Option<(String, Option<Url>)> {
+        let raw = cell.raw();
+
+        match cell.anchor {
+            Some(anchor) => {
+                let url = match anchor.href {
+                    Some(href) => {
+                        let url = link::resolve(&self.base, &href);
+
+                        if url.is_none() {
+                            self.warn(Problem::InvalidUrl(field), href);
+                        }
+
+                        url
+                    },
+                    None => {
+                        self.warn(Problem::MissingHref(field), raw);
+
+                        None
+                    }
+                };
+
+                Some((anchor.text.unwrap_or_default().replace('\n', " "), url))
+            },
             None => {
-                return Ok(None);
+                let problem = if raw.is_empty() { Problem::Missing(field) } else { Problem::Unlinked(field) };
+
+                self.warn(problem, raw);
+
+                None
             }
         }
-    };
+    }
 
-    load_manufacturers_from(&html_content)
-}
+    /// Builds a `Revision` from `row` & files it under its model & manufacturer,
+    /// rows of the same model make up revisions of a single `Model`
+    ///
+    /// Rows without a brand or a model are warned about, but left out of the catalog
+    fn add_row(&mut self, row: Row) {
+        self.rows += 1;
 
-/// Loads all brand details from given `html_content`
-/// This would be ideal in case where you already have device details stored as html, and
-/// it could just be loaded from file & passed
-/// Doesn't suffer from any network issues
-fn load_manufacturers_from(html_content: &str) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
-    let mut manufacturers: Vec<Manufacturer> = Vec::new();
-    let mut manufacturer_models_map: HashMap<String, Vec<Model>> = HashMap::new();
-    let document = Html::parse_document(&html_content);
-    let tr_selector = Selector::parse(".table.dataaggregation tr")
-        .unwrap();
-    let td_selector = Selector::parse("td")
-        .unwrap();
-    let a_selector = Selector::parse("a")
-        .unwrap();
-    let mut tr_nodes = document.select(&tr_selector)
-        .skip(2); // 1st two rows are header & filters
-    
-    while let Some(node) = tr_nodes.next() {
-        let mut td_nodes = node.select(&td_selector) // ideally we get 7 cells
+        if row.cells.len() != EXPECTED_CELLS {
+            let raw = row.cells.iter()
+                .map(|cell| cell.raw())
+                .collect::<Vec<String>>()
+                .join(" | ");
+
+            self.warn(Problem::Shape(row.cells.len()), raw);
+        }
+
+        let mut cells = row.cells
+            .into_iter()
             .skip(1) // skip row number cell
             .take(5); // skips last cell, which is view/edit link
-    
+
         // manufacturer cell
-        if let Some(node) = td_nodes.next() {
-            let manufacturer = node.text()
-                .next()
-                .unwrap();
-            let models = manufacturer_models_map.entry(manufacturer.to_string())
-                .or_insert(Vec::new());
+        if let Some(cell) = cells.next() {
+            let manufacturer = self.text(cell, "brand")
+                .unwrap_or_default();
             let mut model_name: String = "".to_string();
             let mut versions: Vec<String> = Vec::new();
+            let mut versions_text: String = "".to_string();
             let mut openwrt_version: Version = Version::new("".to_string(), "".to_string());
             let mut device_page: String = "".to_string();
-                
+
             // model name cell
-            if let Some(node) = td_nodes.next() {
-                if let Some(val) = node.text().next() {
-                    model_name = val.to_string();
+            if let Some(cell) = cells.next() {
+                if let Some(val) = self.text(cell, "model") {
+                    model_name = val;
                 }
             }
-    
+
             // model versions cell
-            if let Some(node) = td_nodes.next() {
-                if let Some(val) = node.text().next() {
-                    versions = val.to_string()
-                        .split(",")
-                        .map(|version| version.trim().to_string())
-                        .collect::<Vec<String>>();
+            if let Some(cell) = cells.next() {
+                if let Some(val) = cell.text {
+                    versions = data::parse_versions(&val);
+                    versions_text = val.replace('\n', ", ");
                 };
             }
-    
+
             // openwrt version cell
-            if let Some(node) = td_nodes.next() {
-                let mut a_nodes = node.select(&a_selector);
-    
-                if let Some(node) = a_nodes.next() {
-                    let mut version = "".to_string();
-    
-                    if let Some(val) = node.text().next() {
-                        version = val.to_string();
-                    };
-    
-                    let mut link = node.value()
-                        .attr("href")
-                        .unwrap()
-                        .to_string();
-                    link = format!("{}{}", OPENWRT_ROOT_PAGE, link);
-    
+            if let Some(cell) = cells.next() {
+                if let Some((version, url)) = self.link(cell, "release") {
+                    let link = url
+                        .map(String::from)
+                        .unwrap_or_default();
+
                     openwrt_version = Version::new(version, link);
                 }
             }
-            
+
             // release page cell
-            if let Some(node) = td_nodes.next() {
-                let mut a_nodes = node.select(&a_selector);
-    
-                if let Some(node) = a_nodes.next() {
-                    device_page = node.value()
-                        .attr("href")
-                        .unwrap()
-                        .to_string();
-                    device_page = format!("{}{}", OPENWRT_ROOT_PAGE, device_page);
+            if let Some(cell) = cells.next() {
+                if let Some((_, Some(url))) = self.link(cell, "device page") {
+                    device_page = url.into();
                 }
             }
-    
-            let model = Model::new(model_name, versions, openwrt_version, device_page);
-    
-            models.push(model);
+
+            if manufacturer.is_empty() || model_name.is_empty() {
+                return;
+            }
+
+            let revision = Revision::new(versions, openwrt_version, device_page)
+                .with_versions_text(versions_text);
+            let models = self.manufacturer_models_map
+                .entry(manufacturer)
+                .or_default();
+
+            match models.iter_mut().find(|model| data::normalize(model.name()) == data::normalize(&model_name)) {
+                Some(model) => model.add_revision(revision),
+                None => models.push(Model::new(model_name, vec![revision]))
+            }
         }
     }
-    
-    for (manufacturer, models) in manufacturer_models_map {
-        manufacturers.push(Manufacturer::new(manufacturer, models));
+
+    /// Groups collected models by their manufacturer, brands & their models sorted by name so
+    /// the same page always gives the same catalog
+    fn finish(self) -> Parsed {
+        let mut manufacturers = self.manufacturer_models_map
+            .into_iter()
+            .map(|(manufacturer, mut models)| {
+                models.sort_by(|this, that| this.name().cmp(that.name()));
+
+                Manufacturer::new(manufacturer, models)
+            })
+            .collect::<Vec<Manufacturer>>();
+
+        manufacturers.sort_by(|this, that| this.name().cmp(that.name()));
+
+        Parsed {
+            manufacturers: if manufacturers.is_empty() { None } else { Some(manufacturers) },
+            rows: self.rows,
+            warnings: self.warnings,
+            source: String::new()
+        }
     }
-    
-    if manufacturers.len() > 0 {
-        Ok(Some(manufacturers))
+}
+
+/// Parses all brand details from given `html_content` of the page at `base`,
+/// along with warnings about their rows
+pub fn parse_from(html_content: &str, base: Url) -> Parsed {
+    let mut collector = Collector::new(base);
+
+    stream::extract_rows(html_content, |item| collector.add(item));
+
+    collector.finish()
+}
+
+/// Parses all brand details while reading html content of the page at `base` from `reader`,
+/// along with warnings about their rows
+pub fn parse_from_reader<R: Read>(reader: R, base: Url) -> Result<Parsed, Box<dyn Error>> {
+    let mut collector = Collector::new(base);
+
+    stream::extract_rows_from(reader, |item| collector.add(item))?;
+
+    Ok(collector.finish())
+}
+
+/// Loads all brand details from given `html_content`
+/// This would be ideal in case where you already have device details stored as html, and
+/// it could just be loaded from file & passed
+/// Doesn't suffer from any network issues
+///
+/// Rows are picked out of the token stream as they are parsed, see `load_manufacturers_from_dom`
+/// for the equivalent that builds a full DOM
+pub fn load_manufacturers_from(html_content: &str) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+    Ok(parse_from(html_content, default_base()).manufacturers)
+}
+
+/// Loads all brand details while reading html content from `reader`
+/// Same as `load_manufacturers_from`, without having to read the whole page into memory first
+pub fn load_manufacturers_from_reader<R: Read>(reader: R) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+    Ok(parse_from_reader(reader, default_base())?.manufacturers)
+}
+
+/// Returns all text within `element`, with line breaks & blocks separating lines like the stream parser
+fn dom_text(element: ElementRef) -> Option<String> {
+    let mut text: Option<String> = None;
+
+    for node in element.descendants() {
+        match node.value() {
+            Node::Text(val) => text.get_or_insert_with(String::new).push_str(&flatten_whitespace(val)),
+            Node::Element(val) if ["br", "p", "div", "li"].contains(&val.name()) => {
+                text.get_or_insert_with(String::new).push('\n');
+            },
+            _ => ()
+        }
     }
-    else {
-        Ok(None)
+
+    text
+}
+
+/// Extracts the base href & rows of the devices table from a DOM built for `html_content`
+fn extract_rows_dom(html_content: &str) -> Vec<Item> {
+    let document = Html::parse_document(html_content);
+    let base_selector = Selector::parse("base[href]")
+        .unwrap();
+    let tr_selector = Selector::parse(".table.dataaggregation tr")
+        .unwrap();
+    let td_selector = Selector::parse("td")
+        .unwrap();
+    let a_selector = Selector::parse("a")
+        .unwrap();
+
+    let base = document.select(&base_selector)
+        .next()
+        .and_then(|node| node.value().attr("href"))
+        .map(|href| Item::Base(href.to_string()));
+    let rows = document.select(&tr_selector)
+        .skip(2) // 1st two rows are header & filters
+        .map(|node| {
+            let cells = node.select(&td_selector) // ideally we get 7 cells
+                .map(|node| {
+                    let text = dom_text(node);
+                    let anchor = node.select(&a_selector)
+                        .next()
+                        .map(|node| Anchor {
+                            text: dom_text(node),
+                            href: node.value()
+                                .attr("href")
+                                .map(|val| val.to_string())
+                        });
+
+                    Cell { text, anchor }.cleaned()
+                })
+                .collect::<Vec<Cell>>();
+
+            Item::Row(Row { cells })
+        });
+
+    base.into_iter()
+        .chain(rows)
+        .collect()
+}
+
+/// Loads all brand details from given `html_content` by building a full DOM for it
+/// Kept around as reference for `load_manufacturers_from`, which is much lighter on memory
+pub fn load_manufacturers_from_dom(html_content: &str) -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+    let mut collector = Collector::new(default_base());
+
+    for item in extract_rows_dom(html_content) {
+        collector.add(item);
     }
+
+    Ok(collector.finish().manufacturers)
 }
 
 #[cfg(test)]
@@ -201,30 +681,36 @@ mod tests {
         let manufacturers = manufacturers.unwrap();
         assert_eq!(1, manufacturers.len(), "Expected manufacturers, but found none");
 
-        let manufacturer = manufacturers.get(0)
+        let manufacturer = manufacturers.first()
             .unwrap();
         assert_eq!("Abicom International", manufacturer.name());
 
         let model = manufacturer.models()
-            .get(0);
+            .first();
         assert!(model.is_some(), "Expected models, but found none");
 
         let model = model.unwrap();
         assert_eq!("Freedom CPE", model.name());
 
-        let version = model.versions()
-            .get(0);
+        let revision = model.revisions()
+            .first();
+        assert!(revision.is_some(), "Expected revisions, but found none");
+
+        let revision = revision.unwrap();
+        let version = revision.versions()
+            .first();
         assert!(version.is_some(), "Expected versions, but found none");
 
         let version = version.unwrap();
-        assert_eq!("Rev 05", version);
+        assert_eq!("rev05", version);
+        assert_eq!("Rev 05", revision.versions_text());
 
-        let openwrt_version = model.openwrt_version();
+        let openwrt_version = revision.openwrt_version();
         assert_eq!("10.03", openwrt_version.version());
-        assert_eq!(&format!("{}{}", OPENWRT_ROOT_PAGE, "/releases/10.03"), openwrt_version.link());
+        assert_eq!(&"https://openwrt.org/releases/10.03", openwrt_version.link());
 
-        let device_page = model.device_page();
-        assert_eq!(&format!("{}{}", OPENWRT_ROOT_PAGE, "/toh/abicom/freedom_cpe"), device_page);
+        let device_page = revision.device_page();
+        assert_eq!(&"https://openwrt.org/toh/abicom/freedom_cpe", device_page);
     }
 
     #[test]
@@ -326,12 +812,60 @@ mod tests {
         assert_eq!(1, manufacturers.len(), "Expected one manufacturer, but found {}", manufacturers.len());
 
         let manufacturer = manufacturers
-            .get(0)
+            .first()
             .unwrap();
         let models = manufacturer.models();
         assert_eq!(2, models.len(), "Expected multiple models, but found {}", models.len());
     }
 
+    #[test]
+    fn groups_rows_of_a_model_into_revisions() {
+        let html_content = r"
+            <div class='table dataaggregation'>
+                <table>
+                    <tbody>
+                        <tr />
+                        <tr />
+                        <tr>
+                            <td />
+                            <td>TP-Link</td>
+                            <td>Archer C7</td>
+                            <td>v1</td>
+                            <td><a href='/releases/17.01.7'>17.01.7</a></td>
+                            <td><a href='/toh/tp-link/archer_c7_v1'>archer_c7_v1</a></td>
+                            <td />
+                        </tr>
+                        <tr>
+                            <td />
+                            <td>TP-Link</td>
+                            <td>Archer  C7</td>
+                            <td>v2, v3</td>
+                            <td><a href='/releases/19.07.3'>19.07.3</a></td>
+                            <td><a href='/toh/tp-link/archer_c7'>archer_c7</a></td>
+                            <td />
+                        </tr>
+                    </tbody>
+                </table>
+            </div>
+        ";
+
+        let manufacturers = load_manufacturers_from(html_content).unwrap().unwrap();
+        let models = manufacturers.first().unwrap().models();
+        assert_eq!(1, models.len(), "Expected one model, but found {}", models.len());
+
+        let model = models.first().unwrap();
+        assert_eq!(2, model.revisions().len(), "Expected 2 revisions, but found {}", model.revisions().len());
+        assert_eq!("v1, v2, v3", model.versions_text());
+
+        let revision = model.find_revision(Some("V1")).unwrap();
+        assert_eq!("17.01.7", revision.openwrt_version().version());
+        assert_eq!("https://openwrt.org/toh/tp-link/archer_c7_v1", revision.device_page());
+
+        let revision = model.find_revision(None).unwrap();
+        assert_eq!("19.07.3", revision.openwrt_version().version());
+        assert_eq!("https://openwrt.org/toh/tp-link/archer_c7", revision.device_page());
+    }
+
     #[test]
     fn loads_manufacturers_with_model_version_missing() {
         let html_content = r"
@@ -458,14 +992,15 @@ mod tests {
             </div>
         ";
 
-        let manufacturers = load_manufacturers_from(html_content);
-        assert!(manufacturers.is_ok(), "Error loading html");
-
-        let manufacturers = manufacturers.unwrap();
-        assert!(manufacturers.is_some(), "Expected manufacturers, but found none");
+        let parsed = parse_from(html_content, default_base());
+        assert!(parsed.manufacturers.is_none(), "Expected no manufacturers, but found {:?}", parsed.manufacturers);
+        assert_eq!(1, parsed.rows, "Expected 1 row, but found {}", parsed.rows);
 
-        let manufacturers = manufacturers.unwrap();
-        assert_eq!(1, manufacturers.len(), "Expected manufacturers, but found none");
+        let problems = parsed.warnings
+            .iter()
+            .map(|warning| warning.problem)
+            .collect::<Vec<Problem>>();
+        assert_eq!(&[Problem::Missing("brand"), Problem::Missing("model")], &problems[..2]);
     }
 
     #[test]
@@ -500,4 +1035,301 @@ mod tests {
         let manufacturers = manufacturers.unwrap();
         assert!(manufacturers.is_none(), "Expected no manufacturers, but found one");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reports_row_warnings() {
+        let html_content = r"
+            <div class='table dataaggregation'>
+                <table>
+                    <tbody>
+                        <tr />
+                        <tr />
+                        <tr>
+                            <td>1</td>
+                            <td />
+                            <td>Freedom CPE</td>
+                            <td>Rev 05</td>
+                            <td>
+                                <a>10.03</a>
+                            </td>
+                            <td>freedom_cpe</td>
+                            <td />
+                        </tr>
+                        <tr>
+                            <td>2</td>
+                            <td>Actiontec</td>
+                            <td>GT701</td>
+                        </tr>
+                    </tbody>
+                </table>
+            </div>
+        ";
+
+        let parsed = parse_from(html_content, default_base());
+        assert_eq!(2, parsed.rows, "Expected 2 rows, but found {}", parsed.rows);
+        assert_eq!(1, parsed.manufacturers.map(|manufacturers| manufacturers.len()).unwrap_or_default());
+
+        let warnings = parsed.warnings
+            .iter()
+            .map(|warning| (warning.row, warning.problem, warning.raw.as_str()))
+            .collect::<Vec<(usize, Problem, &str)>>();
+        assert_eq!(vec![
+            (1, Problem::Missing("brand"), ""),
+            (1, Problem::MissingHref("release"), "10.03"),
+            (1, Problem::Unlinked("device page"), "freedom_cpe"),
+            (2, Problem::Shape(3), "2 | Actiontec | GT701")
+        ], warnings);
+        assert!(warnings[3].1.is_structural());
+    }
+
+    #[test]
+    fn loads_full_cell_text_and_normalized_versions() {
+        let html_content = r"
+            <div class='table dataaggregation'>
+                <table>
+                    <tbody>
+                        <tr />
+                        <tr />
+                        <tr>
+                            <td>1</td>
+                            <td><span>TP</span>-Link</td>
+                            <td>Archer <a href='/toh/tp-link/archer_c7'>C7</a> AC1750</td>
+                            <td>V1.0/v2<br>v3 - v5</td>
+                            <td>
+                                <a href='/releases/19.07.3'>19.07<b>.3</b></a>
+                            </td>
+                            <td>
+                                <a href='/toh/tp-link/archer_c7'>archer_c7</a>
+                            </td>
+                            <td />
+                        </tr>
+                    </tbody>
+                </table>
+            </div>
+        ";
+
+        for manufacturers in [load_manufacturers_from(html_content), load_manufacturers_from_dom(html_content)] {
+            let manufacturers = manufacturers.unwrap().unwrap();
+            let manufacturer = manufacturers.first().unwrap();
+            assert_eq!("TP-Link", manufacturer.name());
+
+            let model = manufacturer.models().first().unwrap();
+            assert_eq!("Archer C7 AC1750", model.name());
+
+            let revision = model.revisions().first().unwrap();
+            assert_eq!(&vec!["v1", "v2", "v3", "v4", "v5"], revision.versions());
+            assert_eq!("V1.0/v2, v3 - v5", revision.versions_text());
+            assert_eq!("19.07.3", revision.openwrt_version().version());
+        }
+    }
+
+    #[test]
+    fn resolves_links_against_page_base() {
+        let html_content = r"
+            <html>
+                <head><base href='https://mirror.example.com/wiki/'></head>
+                <body>
+                    <div class='table dataaggregation'>
+                        <table>
+                            <tbody>
+                                <tr />
+                                <tr />
+                                <tr>
+                                    <td>1</td>
+                                    <td>TP-Link</td>
+                                    <td>Archer C7</td>
+                                    <td>v2</td>
+                                    <td><a href='https://openwrt.org/releases/19.07.3#changes'>19.07.3</a></td>
+                                    <td><a href='doku.php?id=toh:tp-link:archer_c7'>archer_c7</a></td>
+                                    <td />
+                                </tr>
+                                <tr>
+                                    <td>2</td>
+                                    <td>Netgear</td>
+                                    <td>R7800</td>
+                                    <td />
+                                    <td><a href='//openwrt.org/releases/19.07.3'>19.07.3</a></td>
+                                    <td><a href='netgear/r7800'>r7800</a></td>
+                                    <td />
+                                </tr>
+                            </tbody>
+                        </table>
+                    </div>
+                </body>
+            </html>
+        ";
+
+        for manufacturers in [load_manufacturers_from(html_content), load_manufacturers_from_dom(html_content)] {
+            let manufacturers = manufacturers.unwrap().unwrap();
+            let revision = |brand: &str| data::find_manufacturer(&manufacturers, brand)
+                .and_then(|manufacturer| manufacturer.models().first())
+                .and_then(|model| model.revisions().first())
+                .unwrap();
+
+            assert_eq!("https://openwrt.org/releases/19.07.3", revision("tp-link").openwrt_version().link());
+            assert_eq!("https://mirror.example.com/wiki/doku.php?id=toh:tp-link:archer_c7", revision("tp-link").device_page());
+            assert_eq!("https://openwrt.org/releases/19.07.3", revision("netgear").openwrt_version().link());
+            assert_eq!("https://mirror.example.com/wiki/netgear/r7800", revision("netgear").device_page());
+        }
+    }
+
+    #[test]
+    fn stream_and_dom_extract_same_rows() {
+        let html_content = r"
+            <html>
+                <head>
+                    <script>var row = '<tr><td>1</td><td>Not A Brand</td></tr>';</script>
+                </head>
+                <body>
+                    <div class='table dataaggregation'>
+                        <table>
+                            <thead>
+                                <tr><th>#</th><th>Brand</th></tr>
+                                <tr><th /><th /></tr>
+                            </thead>
+                            <tbody>
+                                <tr>
+                                    <td>1</td>
+                                    <td>Abicom <!-- comment --> International</td>
+                                    <td>Freedom &amp; CPE</td>
+                                    <td>Rev 05, Rev 06</td>
+                                    <td>
+                                        <a href='/releases/10.03'>10.03</a>
+                                    </td>
+                                    <td>
+                                        <a href='/toh/abicom/freedom_cpe'>freedom_cpe</a>
+                                    </td>
+                                    <td />
+                                </tr>
+                                <tr>
+                                    <td>2</td>
+                                    <td>Actiontec</td>
+                                    <td>GT701</td>
+                                    <td />
+                                    <td>
+                                        <a>10.03.1</a>
+                                    </td>
+                                    <td />
+                                    <td />
+                                </tr>
+                            </tbody>
+                        </table>
+                    </div>
+                    <table>
+                        <tr><td>1</td><td>Outside Brand</td></tr>
+                    </table>
+                </body>
+            </html>
+        ";
+
+        let mut rows = Vec::new();
+        stream::extract_rows(html_content, |row| rows.push(row));
+
+        assert_eq!(2, rows.len(), "Expected 2 rows, but found {}", rows.len());
+        assert_eq!(extract_rows_dom(html_content), rows);
+    }
+
+    #[test]
+    fn loads_manufacturers_from_reader_in_chunks() {
+        let mut html_content = String::from(r"
+            <div class='table dataaggregation'>
+                <table>
+                    <tbody>
+                        <tr />
+                        <tr />
+        ");
+
+        // enough rows to span several chunks, with multi byte characters across chunk boundaries
+        for i in 0..500 {
+            html_content.push_str(&format!(r"
+                <tr>
+                    <td>{0}</td>
+                    <td>Brand {1}</td>
+                    <td>Modèl {0}</td>
+                    <td>v1</td>
+                    <td>
+                        <a href='/releases/19.07.3'>19.07.3</a>
+                    </td>
+                    <td>
+                        <a href='/toh/brand/model_{0}'>model_{0}</a>
+                    </td>
+                    <td />
+                </tr>
+            ", i, i % 10));
+        }
+
+        html_content.push_str(r"
+                    </tbody>
+                </table>
+            </div>
+        ");
+
+        let manufacturers = load_manufacturers_from_reader(html_content.as_bytes());
+        assert!(manufacturers.is_ok(), "Error loading html");
+
+        let manufacturers = manufacturers.unwrap();
+        assert!(manufacturers.is_some(), "Expected manufacturers, but found none");
+
+        let manufacturers = manufacturers.unwrap();
+        assert_eq!(10, manufacturers.len(), "Expected 10 manufacturers, but found {}", manufacturers.len());
+
+        let models = manufacturers.iter()
+            .flat_map(|manufacturer| manufacturer.models())
+            .collect::<Vec<&Model>>();
+        assert_eq!(500, models.len(), "Expected 500 models, but found {}", models.len());
+        assert!(models.iter().all(|model| model.name().starts_with("Modèl ")));
+    }
+
+    #[test]
+    fn loads_same_catalog_from_same_page() {
+        let mut html_content = String::from("<div class='table dataaggregation'><table><tbody><tr /><tr />");
+
+        // enough brands & models for any order of them to show
+        for i in (0..50).rev() {
+            html_content.push_str(&format!(r"
+                <tr>
+                    <td>{0}</td>
+                    <td>Brand {1}</td>
+                    <td>Model {0}</td>
+                    <td>v1</td>
+                    <td><a href='/releases/19.07.3'>19.07.3</a></td>
+                    <td><a href='/toh/brand/model_{0}'>model_{0}</a></td>
+                    <td />
+                </tr>
+            ", i, i % 20));
+        }
+
+        html_content.push_str("</tbody></table></div>");
+
+        let catalog = || {
+            let manufacturers = load_manufacturers_from(&html_content).unwrap().unwrap();
+            let brands = manufacturers.iter().collect::<Vec<&Manufacturer>>();
+
+            super::super::schema::Catalog::new(&brands).to_json().unwrap()
+        };
+        let json = catalog();
+
+        for _ in 0..5 {
+            assert_eq!(json, catalog());
+        }
+
+        let manufacturers = load_manufacturers_from(&html_content).unwrap().unwrap();
+        let names = manufacturers.iter()
+            .map(|manufacturer| manufacturer.name())
+            .collect::<Vec<&str>>();
+        assert_eq!(["Brand 0", "Brand 1", "Brand 10"], names[..3]);
+
+        let models = manufacturers[0].models().iter()
+            .map(|model| model.name().as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(vec!["Model 0", "Model 20", "Model 40"], models);
+    }
+
+    #[test]
+    fn tells_fallback_sources() {
+        assert!(is_fallback(ODIN_CACHED_PAGE));
+        assert!(is_fallback(&parse_snapshot(snapshot::Snapshot { date: "2020-05-16", manufacturers: Vec::new() }).source));
+        assert!(!is_fallback(ODIN_DEVICE_PAGE));
+        assert!(!is_fallback(ALL_DEVICES_PAGE));
+    }
+}
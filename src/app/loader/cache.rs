@@ -0,0 +1,215 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+
+/// Least share of the last good page's rows a fetched page may have, anything below hints at
+/// a truncated or partial page
+const MIN_ROWS_RATIO: f64 = 0.5;
+
+/// Most a fetched page may have, as a multiple of the last good page's rows
+const MAX_ROWS_RATIO: f64 = 2.0;
+
+/// Returns sha256 of `content`, hex encoded
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Returns seconds since the unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// # `Meta` represents what's known about a page fetched from the web
+///
+/// `url` - URL it was finally loaded from
+/// `fetched_at` - when it was fetched, in seconds since the unix epoch
+/// `rows` - number of data rows in its devices table
+/// `sha256` - hash of its content
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Meta {
+    pub url: String,
+    pub fetched_at: u64,
+    pub rows: usize,
+    pub sha256: String
+}
+
+impl Meta {
+    /// Describes the page at `url` with `html_content`, fetched just now
+    pub fn new(url: &str, html_content: &str, rows: usize) -> Self {
+        Self { url: url.to_string(), fetched_at: now(), rows, sha256: content_hash(html_content) }
+    }
+
+    /// Returns how long ago the page was fetched, ex: 3 hour(s) ago
+    pub fn age(&self) -> String {
+        let seconds = now().saturating_sub(self.fetched_at);
+
+        match seconds {
+            0..=59 => "just now".to_string(),
+            60..=3_599 => format!("{} minute(s) ago", seconds / 60),
+            3_600..=86_399 => format!("{} hour(s) ago", seconds / 3_600),
+            _ => format!("{} day(s) ago", seconds / 86_400)
+        }
+    }
+}
+
+/// Checks `html_content` of a fetched page, described by `meta`, looks like the devices page,
+/// comparing its rows against the `previous` good page when there's one
+///
+/// Maintenance pages & captchas have no devices table, truncated pages have no end to it or
+/// far fewer rows than before
+pub fn validate(html_content: &str, meta: &Meta, previous: Option<&Meta>) -> Result<(), String> {
+    if !html_content.contains("<table") {
+        return Err("found no devices table, it may be a maintenance or captcha page".to_string());
+    }
+
+    if !html_content.contains("</table>") {
+        return Err("devices table doesn't end, the page may be truncated".to_string());
+    }
+
+    if meta.rows == 0 {
+        return Err("found no devices in the table".to_string());
+    }
+
+    if let Some(previous) = previous {
+        let low = (previous.rows as f64 * MIN_ROWS_RATIO).ceil() as usize;
+        let high = (previous.rows as f64 * MAX_ROWS_RATIO).floor() as usize;
+
+        if meta.rows < low || meta.rows > high {
+            return Err(format!("found {} row(s), expected {} to {} as the last good page had {}",
+                meta.rows, low, high, previous.rows));
+        }
+    }
+
+    Ok(())
+}
+
+/// # `Cache` represents the last good page fetched from the web, along with its `Meta`
+///
+/// `page` - path of the page
+/// `meta` - path of its meta data, next to the page
+pub struct Cache {
+    page: PathBuf,
+    meta: PathBuf
+}
+
+/// Writes `content` to `path` through a temporary file next to it, so that `path` is either
+/// left as it was or fully written
+fn write_atomically(path: &Path, content: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let file_name = path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temporary = path.with_file_name(format!(".{}.{}.tmp", file_name, process::id()));
+
+    fs::write(&temporary, content)
+        .and_then(|_| fs::rename(&temporary, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temporary);
+        })
+}
+
+impl Cache {
+    /// Cache with its page at `page`, ex: ~/.config/odin/cache/devices.html has its meta data
+    /// in ~/.config/odin/cache/devices.meta.json
+    pub fn new(page: &str) -> Self {
+        let page = PathBuf::from(shellexpand::tilde(page).trim());
+        let meta = page.with_extension("meta.json");
+
+        Self { page, meta }
+    }
+
+    /// Returns meta data of the cached page, `None` when there's none
+    pub fn meta(&self) -> Option<Meta> {
+        let content = fs::read(&self.meta).ok()?;
+
+        match serde_json::from_slice(&content) {
+            Ok(meta) => Some(meta),
+            Err(err) => {
+                warn!("Ignoring invalid cache meta data {}: {}", self.meta.display(), err);
+
+                None
+            }
+        }
+    }
+
+    /// Returns the cached page along with its meta data, `None` when there's none or it doesn't
+    /// match its recorded hash
+    pub fn load(&self) -> Option<(String, Meta)> {
+        let meta = self.meta()?;
+        let html_content = fs::read_to_string(&self.page).ok()?;
+
+        if content_hash(&html_content) != meta.sha256 {
+            warn!("Ignoring cached page {}, it doesn't match its recorded hash", self.page.display());
+
+            return None;
+        }
+
+        Some((html_content, meta))
+    }
+
+    /// Caches `html_content` described by `meta`, replacing the previous page
+    ///
+    /// The page is written before its meta data, so a page is never taken for another
+    pub fn store(&self, html_content: &str, meta: &Meta) -> Result<(), Box<dyn Error>> {
+        write_atomically(&self.page, html_content.as_bytes())?;
+        write_atomically(&self.meta, &serde_json::to_vec_pretty(meta)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "<table><tr><td>TP-Link</td></tr></table>";
+
+    #[test]
+    fn rejects_suspicious_pages() {
+        let previous = Meta::new("https://openwrt.org/toh/start", PAGE, 100);
+        let meta = |rows| Meta { rows, ..previous.clone() };
+
+        assert!(validate(PAGE, &meta(100), Some(&previous)).is_ok());
+        assert!(validate(PAGE, &meta(50), Some(&previous)).is_ok());
+        assert!(validate(PAGE, &meta(1), None).is_ok());
+
+        assert_eq!(Err("found no devices table, it may be a maintenance or captcha page".to_string()),
+            validate("<html><body>Down for maintenance</body></html>", &meta(0), None));
+        assert_eq!(Err("devices table doesn't end, the page may be truncated".to_string()),
+            validate("<table><tr><td>TP-Link", &meta(1), None));
+        assert_eq!(Err("found no devices in the table".to_string()),
+            validate("<table></table>", &meta(0), None));
+        assert_eq!(Err("found 49 row(s), expected 50 to 200 as the last good page had 100".to_string()),
+            validate(PAGE, &meta(49), Some(&previous)));
+    }
+
+    #[test]
+    fn stores_and_loads_pages() {
+        let dir = std::env::temp_dir().join(format!("odin-cache-{}", process::id()));
+        let cache = Cache::new(&dir.join("devices.html").to_string_lossy());
+        let meta = Meta::new("https://openwrt.org/toh/start", PAGE, 1);
+
+        assert!(cache.load().is_none());
+
+        cache.store(PAGE, &meta).unwrap();
+        assert_eq!(Some((PAGE.to_string(), meta.clone())), cache.load());
+        assert!(dir.join("devices.meta.json").exists());
+
+        fs::write(dir.join("devices.html"), "<table>").unwrap();
+        assert!(cache.load().is_none());
+        assert_eq!(Some(meta), cache.meta());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
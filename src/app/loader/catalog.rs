@@ -0,0 +1,147 @@
+use std::error::Error;
+use std::io::{self, BufRead, Read};
+use serde_json::Value;
+
+use super::super::Manufacturer;
+use super::super::schema::SCHEMA_VERSION;
+
+/// UTF-8 byte order mark, some editors put it in front of hand written files
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Returns whether content of `reader` is a JSON catalog rather than an html page,
+/// judging by its first character past whitespace & a byte order mark
+///
+/// Only what's been looked at is consumed, so `reader` can be parsed either way afterwards
+pub fn is_json<R: BufRead>(reader: &mut R) -> io::Result<bool> {
+    let mut at_start = true;
+
+    loop {
+        let buffer = reader.fill_buf()?;
+
+        if buffer.is_empty() {
+            return Ok(false);
+        }
+
+        if at_start && buffer.starts_with(BOM) {
+            reader.consume(BOM.len());
+            at_start = false;
+
+            continue;
+        }
+
+        at_start = false;
+
+        match buffer.iter().position(|byte| !byte.is_ascii_whitespace()) {
+            Some(index) => {
+                let is_json = buffer[index] == b'[' || buffer[index] == b'{';
+
+                reader.consume(index);
+
+                return Ok(is_json);
+            },
+            None => {
+                let count = buffer.len();
+
+                reader.consume(count);
+            }
+        }
+    }
+}
+
+/// Parses a JSON catalog from `reader`, one of
+///
+/// 1. A versioned catalog, as written by `odin list --format json`, see `schema::Catalog`
+/// 2. A list of brands, as written before catalogs were versioned
+/// 3. A single brand, as served by `odin serve` on `/brands/<brand>`
+///
+/// Catalogs of a newer `schema_version` than this odin knows of are rejected
+pub fn parse_json<R: Read>(reader: R) -> Result<Vec<Manufacturer>, Box<dyn Error>> {
+    let invalid = |err: serde_json::Error| format!("Invalid JSON catalog: {}", err);
+    let mut catalog: Value = serde_json::from_reader(reader)
+        .map_err(invalid)?;
+
+    if let Some(schema_version) = catalog.get("schema_version") {
+        match schema_version.as_u64() {
+            Some(version) if version <= SCHEMA_VERSION as u64 => (),
+            Some(version) => return Err(format!(
+                "Catalog schema version {} is newer than the supported {}, upgrade odin!", version, SCHEMA_VERSION).into()),
+            None => return Err(format!("Invalid catalog schema version: {}", schema_version).into())
+        }
+
+        catalog = catalog["brands"].take();
+    }
+
+    let manufacturers = if catalog.is_array() {
+        serde_json::from_value(catalog)
+    }
+    else {
+        serde_json::from_value(catalog).map(|manufacturer| vec![manufacturer])
+    };
+
+    Ok(manufacturers.map_err(invalid)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn detects_json_by_content() {
+        let detect = |content: &str| is_json(&mut BufReader::with_capacity(4, content.as_bytes())).unwrap();
+
+        assert!(detect("[]"));
+        assert!(detect("\u{feff}  \n\t { \"name\": \"TP-Link\" }"));
+        assert!(!detect("<!DOCTYPE html><html></html>"));
+        assert!(!detect("     "));
+
+        let mut reader = BufReader::with_capacity(4, "   [{ \"name\": \"TP-Link\", \"models\": [] }]".as_bytes());
+        assert!(is_json(&mut reader).unwrap());
+        assert_eq!(1, parse_json(reader).unwrap().len());
+    }
+
+    #[test]
+    fn parses_hand_written_catalogs() {
+        let manufacturers = parse_json(r#"{
+            "name": "TP-Link",
+            "models": [{
+                "name": "Archer C7",
+                "revisions": [{
+                    "versions": ["V2"],
+                    "openwrt_version": { "version": "19.07.3" }
+                }]
+            }]
+        }"#.as_bytes()).unwrap();
+
+        let (model, revision) = manufacturers[0].find_revision("archer c7", Some("v2")).unwrap();
+        assert_eq!("Archer C7", model.name());
+        assert_eq!("V2", revision.versions_text());
+        assert!(revision.device_page().is_empty());
+
+        let err = parse_json(r#"[{ "name": "TP-Link" }]"#.as_bytes()).unwrap_err();
+        assert_eq!("Invalid JSON catalog: missing field `models`", err.to_string());
+    }
+
+    #[test]
+    fn parses_versioned_catalogs() {
+        let manufacturers = parse_json(r#"{
+            "schema_version": 1,
+            "brands": [{ "name": "TP-Link", "models": [] }, { "name": "Netgear", "models": [] }]
+        }"#.as_bytes()).unwrap();
+        assert_eq!(2, manufacturers.len());
+
+        // written before versions had named fields
+        let manufacturers = parse_json(r#"[{
+            "name": "TP-Link",
+            "models": [{
+                "name": "Archer C7",
+                "revisions": [{ "versions": ["v2"], "openwrt_version": ["19.07.3", "https://openwrt.org/releases/19.07.3"] }]
+            }]
+        }]"#.as_bytes()).unwrap();
+        let (_, revision) = manufacturers[0].find_revision("archer c7", None).unwrap();
+        assert_eq!("https://openwrt.org/releases/19.07.3", revision.openwrt_version().link());
+
+        let err = parse_json(r#"{ "schema_version": 99, "brands": [] }"#.as_bytes()).unwrap_err();
+        assert_eq!("Catalog schema version 99 is newer than the supported 1, upgrade odin!", err.to_string());
+    }
+}
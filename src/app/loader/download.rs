@@ -0,0 +1,240 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, IsTerminal, Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use encoding_rs::{Encoding, UTF_8};
+use mime::Mime;
+
+use crate::log::{self, Level};
+
+/// Largest page accepted from the web by default, in MB
+const DEFAULT_MAX_BODY_MB: u64 = 64;
+
+const MB: u64 = 1024 * 1024;
+
+/// Size of chunks the body is read in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// How often the progress indicator is redrawn
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Largest page accepted from the web, in bytes, see `set_max_body_mb`
+static MAX_BODY_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_MAX_BODY_MB * MB);
+
+/// Longest part of an error page logged, in characters
+const ERROR_BODY_CHARS: usize = 200;
+
+/// Number of live `Interruptible`s & whether Ctrl-C was pressed while there were any
+static INTERRUPTIBLES: AtomicUsize = AtomicUsize::new(0);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Sets the largest page accepted from the web to `mb` MB
+pub fn set_max_body_mb(mb: u64) {
+    MAX_BODY_BYTES.store(mb.saturating_mul(MB), Ordering::SeqCst);
+}
+
+/// Returns the largest page accepted from the web, in bytes
+pub fn max_body_bytes() -> u64 {
+    MAX_BODY_BYTES.load(Ordering::SeqCst)
+}
+
+/// # `Interrupted` represents the error of work cut short by Ctrl-C, see `Interruptible`
+#[derive(Debug)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Interrupted!")
+    }
+}
+
+impl Error for Interrupted {}
+
+/// Notes Ctrl-C for the live `Interruptible`s to wind down, meant to be called by a Ctrl-C handler
+///
+/// Returns `false` when there's no `Interruptible` to note it, the handler should then exit
+/// as an unhandled Ctrl-C would have
+pub fn interrupt() -> bool {
+    if INTERRUPTIBLES.load(Ordering::SeqCst) == 0 {
+        return false;
+    }
+
+    INTERRUPTED.store(true, Ordering::SeqCst);
+
+    true
+}
+
+/// # `Interruptible` represents work Ctrl-C shouldn't cut short, like writing a cache
+///
+/// While any is alive, Ctrl-C is only noted by `interrupt`, for the work to check with `check`
+/// & wind down. Once the last one is gone, Ctrl-C is forgotten, so later work isn't interrupted.
+pub struct Interruptible;
+
+impl Interruptible {
+    /// Begins interruptible work, noting Ctrl-C from now on
+    pub fn begin() -> Self {
+        INTERRUPTIBLES.fetch_add(1, Ordering::SeqCst);
+
+        Self
+    }
+
+    /// Fails with `Interrupted` when Ctrl-C was pressed
+    pub fn check(&self) -> Result<(), Box<dyn Error>> {
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            return Err(Interrupted.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Interruptible {
+    fn drop(&mut self) {
+        if INTERRUPTIBLES.fetch_sub(1, Ordering::SeqCst) == 1 {
+            INTERRUPTED.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Decodes `body` with the charset of `content_type`, UTF-8 when it has none or an unknown one,
+/// the way `reqwest`'s `text` does
+pub fn decode(body: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(|content_type| content_type.parse::<Mime>().ok())
+        .and_then(|mime| mime.get_param("charset")
+            .and_then(|charset| Encoding::for_label(charset.as_str().as_bytes())))
+        .unwrap_or(UTF_8);
+
+    encoding.decode(body).0.into_owned()
+}
+
+/// Returns the first non blank line of an error page `body`, cut at `ERROR_BODY_CHARS`
+pub fn summarize(body: &str) -> String {
+    let line = body.lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+
+    match line.char_indices().nth(ERROR_BODY_CHARS) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string()
+    }
+}
+
+/// # `Progress` represents a download progress indicator on stderr, drawn only when stderr is
+/// a terminal & info diagnostics are shown
+///
+/// `total` - expected size in bytes, when the server told
+struct Progress {
+    shown: bool,
+    total: Option<u64>,
+    drawn_at: Option<Instant>
+}
+
+impl Progress {
+    fn new(total: Option<u64>) -> Self {
+        let shown = io::stderr().is_terminal() && log::enabled(Level::Info, module_path!());
+
+        Self { shown, total, drawn_at: None }
+    }
+
+    /// Redraws the indicator with `read` bytes, at most every `PROGRESS_INTERVAL`
+    fn update(&mut self, read: u64) {
+        if !self.shown || self.drawn_at.is_some_and(|drawn_at| drawn_at.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+
+        let mb = |bytes: u64| bytes as f64 / MB as f64;
+        let text = match self.total.filter(|total| *total > 0) {
+            Some(total) => format!("Downloaded {:.1} of {:.1} MB ({}%)", mb(read), mb(total), read * 100 / total),
+            None => format!("Downloaded {:.1} MB", mb(read))
+        };
+
+        eprint!("\r{}\x1b[K", text);
+        let _ = io::stderr().flush();
+
+        self.drawn_at = Some(Instant::now());
+    }
+}
+
+impl Drop for Progress {
+    /// Clears the indicator, leaving stderr as it was
+    fn drop(&mut self) {
+        if self.shown && self.drawn_at.is_some() {
+            eprint!("\r\x1b[K");
+            let _ = io::stderr().flush();
+        }
+    }
+}
+
+/// Reads the body of `url` from `reader` in chunks, with progress out of `total` bytes when known,
+/// failing once it grows beyond `limit` bytes or Ctrl-C was pressed, see `Interruptible`
+pub fn read_body<R: Read>(mut reader: R, url: &str, total: Option<u64>, limit: u64, interruptible: &Interruptible) -> Result<Vec<u8>, Box<dyn Error>> {
+    let too_large = || format!("{} is larger than {} MB, see --max-body-size", url, limit / MB);
+
+    if total.is_some_and(|total| total > limit) {
+        return Err(too_large().into());
+    }
+
+    let mut progress = Progress::new(total);
+    let mut body = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+
+    loop {
+        interruptible.check()?;
+
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into())
+        };
+
+        if body.len() as u64 + read as u64 > limit {
+            return Err(too_large().into());
+        }
+
+        body.extend_from_slice(&chunk[..read]);
+        progress.update(body.len() as u64);
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_bodies_up_to_limit() {
+        let interruptible = Interruptible::begin();
+        let page = vec![b'x'; MB as usize + 10];
+
+        assert_eq!(page, read_body(&page[..], "page", None, 2 * MB, &interruptible).unwrap());
+
+        assert_eq!("page is larger than 1 MB, see --max-body-size",
+            read_body(&page[..], "page", None, MB, &interruptible).unwrap_err().to_string());
+        assert_eq!("page is larger than 2 MB, see --max-body-size",
+            read_body(&page[..], "page", Some(3 * MB), 2 * MB, &interruptible).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn decodes_bodies_with_charset() {
+        let latin1 = b"Caf\xe9";
+
+        assert_eq!("Café", decode(latin1, Some("text/html; charset=ISO-8859-1")));
+        assert_eq!("Café", decode("Café".as_bytes(), Some("text/html; charset=utf-8")));
+        assert_eq!("Café", decode("Café".as_bytes(), Some("text/html")));
+        assert_eq!("Café", decode("Café".as_bytes(), None));
+        assert_eq!("Caf\u{fffd}", decode(latin1, Some("text/html; charset=unknown")));
+    }
+
+    #[test]
+    fn summarizes_error_pages() {
+        assert_eq!("<h1>Not Found</h1>", summarize("\n  \n<h1>Not Found</h1>\n<p>Nothing here</p>"));
+        assert_eq!(format!("{}...", "x".repeat(ERROR_BODY_CHARS)), summarize(&"x".repeat(ERROR_BODY_CHARS + 1)));
+        assert_eq!("", summarize(""));
+    }
+}
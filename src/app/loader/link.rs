@@ -0,0 +1,54 @@
+use reqwest::Url;
+
+/// Resolves `href` against `base`, like a browser following the link would
+///
+/// Fragments are dropped, so that links to sections of the same page compare equal. DokuWiki's
+/// `doku.php?id=` links are kept as they are, as a mirror may not rewrite them into page paths.
+/// Returns `None` for hrefs that don't resolve to an http(s) URL.
+///
+/// ## Example
+///
+/// "/toh/tp-link/archer_c7#specifications" -> "https://openwrt.org/toh/tp-link/archer_c7"
+/// "doku.php?id=toh:tp-link:archer_c7" -> "https://openwrt.org/toh/doku.php?id=toh:tp-link:archer_c7"
+pub fn resolve(base: &Url, href: &str) -> Option<Url> {
+    let mut url = base.join(href.trim()).ok()?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return None;
+    }
+
+    url.set_fragment(None);
+
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_links_against_base() {
+        let base = Url::parse("https://openwrt.org/toh/start").unwrap();
+        let resolve = |href: &str| resolve(&base, href).map(|url| url.to_string());
+
+        assert_eq!(Some("https://openwrt.org/releases/19.07.3".to_string()), resolve("/releases/19.07.3"));
+        assert_eq!(Some("https://openwrt.org/toh/tp-link/archer_c7".to_string()), resolve("https://openwrt.org/toh/tp-link/archer_c7"));
+        assert_eq!(Some("https://mirror.example.com/toh/netgear/r7800".to_string()), resolve("//mirror.example.com/toh/netgear/r7800"));
+        assert_eq!(Some("https://openwrt.org/toh/tp-link/archer_c7".to_string()), resolve("/toh/tp-link/archer_c7#specifications"));
+        assert_eq!(Some("https://openwrt.org/doku.php?id=toh:tp-link:archer_c7".to_string()), resolve("/doku.php?id=toh:tp-link:archer_c7"));
+        assert_eq!(Some("https://openwrt.org/toh/doku.php?id=toh:tp-link:archer_c7&rev=1".to_string()), resolve("doku.php?id=toh:tp-link:archer_c7&rev=1#start"));
+        assert_eq!(Some("https://openwrt.org/toh/netgear".to_string()), resolve("netgear"));
+        assert_eq!(None, resolve("javascript:void(0)"));
+        assert_eq!(None, resolve("https://[::1"));
+    }
+
+    #[test]
+    fn resolves_links_against_mirror_base_path() {
+        let base = Url::parse("https://mirror.example.com/wiki/").unwrap();
+        let resolve = |href: &str| resolve(&base, href).map(|url| url.to_string());
+
+        assert_eq!(Some("https://mirror.example.com/wiki/doku.php?id=toh:tp-link:archer_c7".to_string()), resolve("doku.php?id=toh:tp-link:archer_c7"));
+        assert_eq!(Some("https://mirror.example.com/wiki/toh/tp-link/archer_c7".to_string()), resolve("toh/tp-link/archer_c7#specifications"));
+        assert_eq!(Some("https://mirror.example.com/releases/19.07.3".to_string()), resolve("/releases/19.07.3"));
+    }
+}
@@ -0,0 +1,67 @@
+use std::error::Error;
+
+use super::super::Manufacturer;
+
+/// Catalog compressed into the binary by `build.rs`, with the `bundled-snapshot` feature, empty
+/// when there was no catalog to bundle
+#[cfg(feature = "bundled-snapshot")]
+const SNAPSHOT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/snapshot.json.gz"));
+
+/// Date the bundled catalog was taken, as YYYY-MM-DD
+#[cfg(feature = "bundled-snapshot")]
+const SNAPSHOT_DATE: &str = env!("ODIN_SNAPSHOT_DATE");
+
+/// # `Snapshot` represents the catalog bundled into the binary
+///
+/// `date` - date it was taken, as YYYY-MM-DD
+/// `manufacturers` - all of its brands
+pub struct Snapshot {
+    pub date: &'static str,
+    pub manufacturers: Vec<Manufacturer>
+}
+
+/// Returns the catalog bundled into the binary, `None` when built without the `bundled-snapshot` feature
+/// or without a catalog to bundle
+///
+/// Meant as a last resort only, when neither a file nor the web could be loaded, it may be
+/// well out of date
+#[cfg(feature = "bundled-snapshot")]
+pub fn load() -> Result<Option<Snapshot>, Box<dyn Error>> {
+    if SNAPSHOT.is_empty() {
+        warn!("No snapshot was bundled, build with snapshot/catalog.json or ODIN_SNAPSHOT to get one!");
+
+        return Ok(None);
+    }
+
+    let manufacturers = super::catalog::parse_json(flate2::read::GzDecoder::new(SNAPSHOT))
+        .map_err(|err| format!("Bundled snapshot: {}", err))?;
+
+    Ok(Some(Snapshot { date: SNAPSHOT_DATE, manufacturers }))
+}
+
+#[cfg(not(feature = "bundled-snapshot"))]
+pub fn load() -> Result<Option<Snapshot>, Box<dyn Error>> {
+    Ok(None)
+}
+
+#[cfg(all(test, feature = "bundled-snapshot"))]
+mod tests {
+    use flate2::read::GzDecoder;
+
+    use super::super::catalog;
+    use super::*;
+
+    #[test]
+    fn loads_bundled_snapshot() {
+        if SNAPSHOT.is_empty() {
+            assert!(load().unwrap().is_none());
+            return;
+        }
+
+        let manufacturers = catalog::parse_json(GzDecoder::new(SNAPSHOT)).unwrap();
+
+        assert!(!manufacturers.is_empty());
+        assert_eq!(load().unwrap().unwrap().manufacturers.len(), manufacturers.len());
+        assert_eq!(SNAPSHOT_DATE.len(), "YYYY-MM-DD".len());
+    }
+}
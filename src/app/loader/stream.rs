@@ -0,0 +1,267 @@
+use std::error::Error;
+use std::io::Read;
+use std::str;
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts
+};
+use html5ever::tokenizer::states::RawKind;
+
+use super::{Anchor, Cell, Row};
+
+/// Number of bytes read from the source per tokenizer feed
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Number of leading rows in the devices table, which are header & filters
+const HEADER_ROWS: usize = 2;
+
+/// What's picked out of the page, in page order
+#[derive(Debug, PartialEq)]
+pub enum Item {
+    /// href of the page's `<base>`, which links are relative to
+    Base(String),
+    /// A data row of the devices table
+    Row(Row)
+}
+
+/// `TokenSink` that picks rows of the devices table out of the token stream,
+/// without ever building a DOM
+///
+/// Mirrors the `.table.dataaggregation tr` selector used by the DOM parser,
+/// rows are handed to `on_item` as soon as they are complete
+struct RowSink<F: FnMut(Item)> {
+    on_item: F,
+    /// name of the element that opened the devices table container
+    container: Option<String>,
+    /// open elements named like the container, to find its end tag
+    container_depth: usize,
+    /// number of `tr` seen so far, header rows included
+    rows_seen: usize,
+    row: Option<Row>,
+    cell: Option<Cell>,
+    /// whether we are within the first anchor of current cell
+    in_anchor: bool
+}
+
+impl<F: FnMut(Item)> RowSink<F> {
+    fn new(on_item: F) -> Self {
+        Self {
+            on_item,
+            container: None,
+            container_depth: 0,
+            rows_seen: 0,
+            row: None,
+            cell: None,
+            in_anchor: false
+        }
+    }
+
+    fn is_container(tag: &Tag) -> bool {
+        tag.attrs.iter()
+            .filter(|attr| &*attr.name.local == "class")
+            .any(|attr| {
+                let classes = attr.value.split_whitespace()
+                    .collect::<Vec<&str>>();
+
+                classes.contains(&"table") && classes.contains(&"dataaggregation")
+            })
+    }
+
+    fn finish_cell(&mut self) {
+        if let Some(cell) = self.cell.take() {
+            if let Some(row) = self.row.as_mut() {
+                row.cells.push(cell.cleaned());
+            }
+        }
+
+        self.in_anchor = false;
+    }
+
+    fn finish_row(&mut self) {
+        self.finish_cell();
+
+        if let Some(row) = self.row.take() {
+            if self.rows_seen > HEADER_ROWS {
+                (self.on_item)(Item::Row(row));
+            }
+        }
+    }
+
+    fn start_row(&mut self) {
+        self.finish_row();
+        self.rows_seen += 1;
+        self.row = Some(Row::default());
+    }
+
+    fn process_tag(&mut self, tag: Tag) {
+        let name = &*tag.name;
+
+        if self.container.is_none() {
+            if tag.kind == TagKind::StartTag && name == "base" {
+                let href = tag.attrs.iter()
+                    .find(|attr| &*attr.name.local == "href")
+                    .map(|attr| attr.value.to_string());
+
+                if let Some(href) = href {
+                    (self.on_item)(Item::Base(href));
+                }
+            }
+            else if tag.kind == TagKind::StartTag && Self::is_container(&tag) {
+                self.container = Some(name.to_string());
+                self.container_depth = 1;
+            }
+
+            return;
+        }
+
+        let is_container_tag = self.container.as_deref() == Some(name);
+
+        match (tag.kind, name) {
+            (TagKind::StartTag, _) if is_container_tag && !tag.self_closing => {
+                self.container_depth += 1;
+            },
+            (TagKind::EndTag, _) if is_container_tag => {
+                self.container_depth -= 1;
+
+                if self.container_depth == 0 {
+                    self.finish_row();
+                    self.container = None;
+                }
+            },
+            (TagKind::StartTag, "tr") => self.start_row(),
+            (TagKind::EndTag, "tr") | (TagKind::EndTag, "table") => self.finish_row(),
+            (TagKind::StartTag, "td") => {
+                // a cell without a row gets one implied, like the tree builder does
+                if self.row.is_none() {
+                    self.start_row();
+                }
+
+                self.finish_cell();
+                self.cell = Some(Cell::default());
+            },
+            (TagKind::StartTag, "th") | (TagKind::EndTag, "td") | (TagKind::EndTag, "th") => {
+                self.finish_cell();
+            },
+            (TagKind::StartTag, "a") => {
+                if let Some(cell) = self.cell.as_mut() {
+                    if cell.anchor.is_none() {
+                        let href = tag.attrs.iter()
+                            .find(|attr| &*attr.name.local == "href")
+                            .map(|attr| attr.value.to_string());
+
+                        cell.anchor = Some(Anchor { text: None, href });
+                        self.in_anchor = true;
+                    }
+                }
+            },
+            (TagKind::EndTag, "a") => self.in_anchor = false,
+            // line breaks & blocks separate lines of text, like they do when rendered
+            (TagKind::StartTag, "br") | (TagKind::StartTag, "p") | (TagKind::StartTag, "div") | (TagKind::StartTag, "li") => {
+                self.push_text("\n");
+            },
+            _ => ()
+        }
+    }
+
+    /// Appends `text` to current cell & its anchor, when within one
+    fn push_text(&mut self, text: &str) {
+        let in_anchor = self.in_anchor;
+
+        if let Some(cell) = self.cell.as_mut() {
+            cell.text.get_or_insert_with(String::new).push_str(text);
+
+            if in_anchor {
+                if let Some(anchor) = cell.anchor.as_mut() {
+                    anchor.text.get_or_insert_with(String::new).push_str(text);
+                }
+            }
+        }
+    }
+
+    fn process_text(&mut self, text: StrTendril) {
+        // line breaks in the source are just whitespace
+        self.push_text(&super::flatten_whitespace(&text));
+    }
+}
+
+impl<F: FnMut(Item)> TokenSink for RowSink<F> {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) => {
+                // without a tree builder, the tokenizer has to be told about raw text elements
+                let raw_kind = match (tag.kind, &*tag.name) {
+                    (TagKind::StartTag, "script") => Some(RawKind::ScriptData),
+                    (TagKind::StartTag, "style") => Some(RawKind::Rawtext),
+                    (TagKind::StartTag, "title") | (TagKind::StartTag, "textarea") => Some(RawKind::Rcdata),
+                    _ => None
+                };
+
+                self.process_tag(tag);
+
+                if let Some(raw_kind) = raw_kind {
+                    return TokenSinkResult::RawData(raw_kind);
+                }
+            },
+            Token::CharacterTokens(text) => self.process_text(text),
+            Token::EOFToken => self.finish_row(),
+            _ => ()
+        }
+
+        TokenSinkResult::Continue
+    }
+}
+
+/// Extracts rows of the devices table from `html_content`, calling `on_item` for each of them
+/// & for the page's base href
+pub fn extract_rows<F: FnMut(Item)>(html_content: &str, on_item: F) {
+    let mut tokenizer = Tokenizer::new(RowSink::new(on_item), TokenizerOpts::default());
+    let mut input = BufferQueue::new();
+
+    input.push_back(StrTendril::from_slice(html_content));
+
+    let _ = tokenizer.feed(&mut input);
+    tokenizer.end();
+}
+
+/// Extracts rows of the devices table while reading html content from `reader`,
+/// calling `on_item` for each of them & for the page's base href
+///
+/// Content is fed to the tokenizer in chunks, so the whole page is never held in memory
+pub fn extract_rows_from<R: Read, F: FnMut(Item)>(mut reader: R, on_item: F) -> Result<(), Box<dyn Error>> {
+    let mut tokenizer = Tokenizer::new(RowSink::new(on_item), TokenizerOpts::default());
+    let mut input = BufferQueue::new();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    // bytes of a multi byte character split across two reads
+    let mut pending: Vec<u8> = Vec::new();
+
+    loop {
+        let count = reader.read(&mut buffer)?;
+
+        if count == 0 {
+            break;
+        }
+
+        pending.extend_from_slice(&buffer[..count]);
+
+        let valid_up_to = match str::from_utf8(&pending) {
+            Ok(_) => pending.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(Box::new(err))
+        };
+
+        input.push_back(StrTendril::from_slice(str::from_utf8(&pending[..valid_up_to])?));
+        pending.drain(..valid_up_to);
+
+        let _ = tokenizer.feed(&mut input);
+    }
+
+    if !pending.is_empty() {
+        str::from_utf8(&pending)?;
+    }
+
+    tokenizer.end();
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::fmt::{self, Display};
+
+/// Number of `td` cells in a row of the devices table: row number, brand, model, versions,
+/// supported release, device page & edit link
+pub const EXPECTED_CELLS: usize = 7;
+
+/// # `Problem` represents what's wrong with a row of the devices table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Problem {
+    /// Row doesn't have `EXPECTED_CELLS` cells, so its cells can't be trusted
+    Shape(usize),
+    /// Cell is empty
+    Missing(&'static str),
+    /// Cell has text, but no link
+    Unlinked(&'static str),
+    /// Cell has a link without `href`
+    MissingHref(&'static str),
+    /// Cell has a link that doesn't resolve to an http(s) URL
+    InvalidUrl(&'static str)
+}
+
+impl Problem {
+    /// Returns whether this problem hints at the page layout having changed,
+    /// rather than at an incomplete entry
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Problem::Shape(_))
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Shape(cells) => write!(f, "has {} cell(s) instead of {}", cells, EXPECTED_CELLS),
+            Problem::Missing(field) => write!(f, "missing {}", field),
+            Problem::Unlinked(field) => write!(f, "{} isn't a link", field),
+            Problem::MissingHref(field) => write!(f, "{} link has no href", field),
+            Problem::InvalidUrl(field) => write!(f, "{} link isn't a valid URL", field)
+        }
+    }
+}
+
+/// # `RowWarning` represents a problem found while parsing a row of the devices table
+///
+/// `row` - number of the row, counting from the first data row
+/// `problem` - what's wrong with the row
+/// `raw` - raw text of the offending cell, or of the whole row for structural problems
+#[derive(Debug, PartialEq)]
+pub struct RowWarning {
+    pub row: usize,
+    pub problem: Problem,
+    pub raw: String
+}
+
+impl Display for RowWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Row {}: {} (raw: {:?})", self.row, self.problem, self.raw)
+    }
+}
@@ -0,0 +1,739 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use colored::*;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use super::data::{Hardware, Manufacturer, ReleaseNumber, Revision};
+
+/// # `Field` represents a field of hardware revisions queries can look at
+///
+/// Hardware versions are the only field with several values, a comparison holds when
+/// it holds for any of them. Technical fields are unknown without `--hardware`, see `Hardware`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Brand,
+    Model,
+    HwVersion,
+    Release,
+    DevicePage,
+    Soc,
+    FlashMb,
+    RamMb,
+    Wlan24Ghz,
+    Wlan50Ghz,
+    EthernetPorts
+}
+
+/// Fields by their name in queries
+const FIELDS: [(&str, Field); 11] = [
+    ("brand", Field::Brand),
+    ("model", Field::Model),
+    ("hw_version", Field::HwVersion),
+    ("release", Field::Release),
+    ("device_page", Field::DevicePage),
+    ("soc", Field::Soc),
+    ("flash_mb", Field::FlashMb),
+    ("ram_mb", Field::RamMb),
+    ("wlan_24ghz", Field::Wlan24Ghz),
+    ("wlan_50ghz", Field::Wlan50Ghz),
+    ("ethernet_ports", Field::EthernetPorts)
+];
+
+/// How values of a field are compared
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Text,
+    Number,
+    Release
+}
+
+impl Field {
+    fn kind(&self) -> Kind {
+        match self {
+            Field::Release => Kind::Release,
+            Field::FlashMb | Field::RamMb | Field::EthernetPorts => Kind::Number,
+            _ => Kind::Text
+        }
+    }
+
+    /// Returns whether this field comes from technical data, see `Hardware`
+    fn is_technical(&self) -> bool {
+        matches!(self, Field::Soc | Field::FlashMb | Field::RamMb | Field::Wlan24Ghz | Field::Wlan50Ghz | Field::EthernetPorts)
+    }
+
+    /// Returns values of this field for `revision` of `model` by `brand`, none when unknown
+    fn values(&self, brand: &str, model: &str, revision: &Revision) -> Vec<String> {
+        let hardware = revision.hardware();
+        let number = |value: Option<u32>| value.map(|value| value.to_string());
+        let values = match self {
+            Field::Brand => vec![brand.to_string()],
+            Field::Model => vec![model.to_string()],
+            Field::HwVersion => revision.versions().clone(),
+            Field::Release => vec![revision.openwrt_version().version().clone()],
+            Field::DevicePage => vec![revision.device_page().clone()],
+            Field::Soc => hardware.map(|hardware| hardware.soc.clone()).into_iter().collect(),
+            Field::FlashMb => number(hardware.and_then(|hardware| hardware.flash_mb)).into_iter().collect(),
+            Field::RamMb => number(hardware.and_then(|hardware| hardware.ram_mb)).into_iter().collect(),
+            Field::Wlan24Ghz => hardware.map(|hardware| hardware.wlan_24ghz.clone()).into_iter().collect(),
+            Field::Wlan50Ghz => hardware.map(|hardware| hardware.wlan_50ghz.clone()).into_iter().collect(),
+            Field::EthernetPorts => number(hardware.and_then(Hardware::ethernet_ports)).into_iter().collect()
+        };
+
+        values.into_iter()
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// Orders values of this field the way they compare, ex: releases by release order
+    fn cmp_values(&self, this: &str, that: &str) -> Ordering {
+        match self.kind() {
+            Kind::Number => this.parse::<u32>().ok().cmp(&that.parse::<u32>().ok()),
+            Kind::Release => this.parse::<ReleaseNumber>().ok().cmp(&that.parse::<ReleaseNumber>().ok()),
+            Kind::Text => this.to_lowercase().cmp(&that.to_lowercase())
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        FIELDS.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(text))
+            .map(|(_, field)| *field)
+            .ok_or_else(|| format!("Unknown field: {}, expected one of {}", text, FIELDS.iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<&str>>()
+                .join(", ")))
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = FIELDS.iter()
+            .find(|(_, field)| field == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default();
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A comparison operator, `!=` & `!~` are parsed as the negation of `=` & `~`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge
+}
+
+impl Op {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less
+        }
+    }
+}
+
+/// A value compared against, read as the kind of the field it's compared with
+#[derive(Debug)]
+enum Literal {
+    Text(String),
+    Number(u32),
+    Release(ReleaseNumber)
+}
+
+impl Literal {
+    fn new(field: Field, text: &str) -> Result<Self, String> {
+        match field.kind() {
+            Kind::Text => Ok(Literal::Text(text.to_lowercase())),
+            Kind::Number => text.parse()
+                .map(Literal::Number)
+                .map_err(|_| format!("{} is a number, got {}", field, text)),
+            Kind::Release => text.parse()
+                .map(Literal::Release)
+                .map_err(|_| format!("{} is a release, ex: 19.07, got {}", field, text))
+        }
+    }
+
+    /// Orders `value` against this literal, `None` when it isn't of the same kind
+    ///
+    /// Text is compared ignoring case & releases only as precisely as written, so 19.07 takes
+    /// in all of its point releases
+    fn cmp(&self, value: &str) -> Option<Ordering> {
+        match self {
+            Literal::Text(text) => Some(value.to_lowercase().cmp(text)),
+            Literal::Number(number) => value.parse::<u32>().ok().map(|value| value.cmp(number)),
+            Literal::Release(release) => value.parse::<ReleaseNumber>().ok().map(|value| value.cmp_up_to(release))
+        }
+    }
+}
+
+/// A parsed query expression
+#[derive(Debug)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Literal),
+    In(Field, Vec<Literal>),
+    Matches(Field, Regex)
+}
+
+impl Expr {
+    fn eval(&self, brand: &str, model: &str, revision: &Revision) -> bool {
+        match self {
+            Expr::Or(left, right) => left.eval(brand, model, revision) || right.eval(brand, model, revision),
+            Expr::And(left, right) => left.eval(brand, model, revision) && right.eval(brand, model, revision),
+            Expr::Not(expr) => !expr.eval(brand, model, revision),
+            Expr::Compare(field, op, literal) => field.values(brand, model, revision)
+                .iter()
+                .any(|value| literal.cmp(value).is_some_and(|ordering| op.holds(ordering))),
+            Expr::In(field, literals) => field.values(brand, model, revision)
+                .iter()
+                .any(|value| literals.iter().any(|literal| literal.cmp(value) == Some(Ordering::Equal))),
+            Expr::Matches(field, regex) => field.values(brand, model, revision)
+                .iter()
+                .any(|value| regex.is_match(value))
+        }
+    }
+
+    fn is_technical(&self) -> bool {
+        match self {
+            Expr::Or(left, right) | Expr::And(left, right) => left.is_technical() || right.is_technical(),
+            Expr::Not(expr) => expr.is_technical(),
+            Expr::Compare(field, _, _) | Expr::In(field, _) | Expr::Matches(field, _) => field.is_technical()
+        }
+    }
+}
+
+/// A token of a query, `Text` is quoted, `Word` isn't
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    OpenList,
+    CloseList,
+    Comma,
+    Op(&'static str),
+    Text(String),
+    Word(String)
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::OpenList => write!(f, "["),
+            Token::CloseList => write!(f, "]"),
+            Token::Comma => write!(f, ","),
+            Token::Op(op) => write!(f, "{}", op),
+            Token::Text(text) => write!(f, "\"{}\"", text),
+            Token::Word(word) => write!(f, "{}", word)
+        }
+    }
+}
+
+/// Operators, longest first so `<=` isn't taken for `<`
+const OPS: [&str; 9] = ["==", "!=", "<=", ">=", "!~", "=", "<", ">", "~"];
+
+/// Splits `query` into tokens, each with the column it starts at
+///
+/// Text is quoted with `"` or `'`, a backslash keeps the quote in it, other backslashes
+/// are kept as written for regular expressions, ex: "v\d+"
+fn tokenize(query: &str) -> Result<Vec<(usize, Token)>, String> {
+    let chars = query.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+        let rest = chars[i..].iter().collect::<String>();
+
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '(' | ')' | '[' | ']' | ',' => {
+                tokens.push((column, match chars[i] {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    '[' => Token::OpenList,
+                    ']' => Token::CloseList,
+                    _ => Token::Comma
+                }));
+                i += 1;
+            },
+            quote @ ('"' | '\'') => {
+                let mut text = String::new();
+                i += 1;
+
+                loop {
+                    match chars.get(i) {
+                        None => return Err(format!("Invalid query at {}: text isn't closed with {}", column, quote)),
+                        Some('\\') if chars.get(i + 1) == Some(&quote) => {
+                            text.push(quote);
+                            i += 2;
+                        },
+                        Some(c) if *c == quote => break,
+                        Some(c) => {
+                            text.push(*c);
+                            i += 1;
+                        }
+                    }
+                }
+
+                tokens.push((column, Token::Text(text)));
+                i += 1;
+            },
+            _ => match OPS.iter().find(|op| rest.starts_with(*op)) {
+                Some(op) => {
+                    tokens.push((column, Token::Op(op)));
+                    i += op.len();
+                },
+                None if "!=<>~".contains(chars[i]) => {
+                    return Err(format!("Invalid query at {}: unknown operator {}", column, chars[i]));
+                },
+                None => {
+                    let word = rest.chars()
+                        .take_while(|c| !c.is_whitespace() && !"()[],\"'!=<>~".contains(*c))
+                        .collect::<String>();
+
+                    i += word.chars().count();
+                    tokens.push((column, Token::Word(word)));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser of queries, from the loosest binding `or` down to comparisons
+///
+/// `end` - column right after the query, where running out of tokens is reported
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    end: usize
+}
+
+impl Parser {
+    fn error<T>(&self, message: String) -> Result<T, String> {
+        let column = self.tokens.get(self.position)
+            .map(|(column, _)| *column)
+            .unwrap_or(self.end);
+
+        Err(format!("Invalid query at {}: {}", column, message))
+    }
+
+    /// Describes the next token for errors
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(token) => format!("found {}", token),
+            None => "found the end of the query".to_string()
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    /// Consumes the next token when it's `token`
+    fn accept(&mut self, token: &Token) -> bool {
+        let accepted = self.peek() == Some(token);
+
+        if accepted {
+            self.position += 1;
+        }
+
+        accepted
+    }
+
+    /// Consumes the next token when it's the keyword `keyword`, in any case
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let accepted = matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+
+        if accepted {
+            self.position += 1;
+        }
+
+        accepted
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        if !self.accept(&token) {
+            return self.error(format!("expected {}, {}", token, self.found()));
+        }
+
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.not()?;
+
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+
+        if self.accept(&Token::Open) {
+            let expr = self.or()?;
+
+            self.expect(Token::Close)?;
+
+            return Ok(expr);
+        }
+
+        self.comparison()
+    }
+
+    fn field(&mut self) -> Result<Field, String> {
+        match self.peek() {
+            Some(Token::Word(word)) => {
+                let field = word.parse::<Field>();
+
+                match field {
+                    Ok(field) => {
+                        self.position += 1;
+
+                        Ok(field)
+                    },
+                    Err(err) => self.error(err)
+                }
+            },
+            _ => self.error(format!("expected a field, {}", self.found()))
+        }
+    }
+
+    /// Reads the next value as a literal of `field`
+    fn literal(&mut self, field: Field) -> Result<Literal, String> {
+        match self.peek() {
+            Some(Token::Text(text)) | Some(Token::Word(text)) => {
+                let literal = Literal::new(field, text);
+
+                match literal {
+                    Ok(literal) => {
+                        self.position += 1;
+
+                        Ok(literal)
+                    },
+                    Err(err) => self.error(err)
+                }
+            },
+            _ => self.error(format!("expected a value, {}", self.found()))
+        }
+    }
+
+    /// Reads a `[value, ...]` list of literals of `field`
+    fn list(&mut self, field: Field) -> Result<Vec<Literal>, String> {
+        let mut literals = Vec::new();
+
+        self.expect(Token::OpenList)?;
+
+        if self.accept(&Token::CloseList) {
+            return Ok(literals);
+        }
+
+        loop {
+            literals.push(self.literal(field)?);
+
+            if self.accept(&Token::CloseList) {
+                return Ok(literals);
+            }
+
+            self.expect(Token::Comma)?;
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let field = self.field()?;
+
+        if self.keyword("in") {
+            return Ok(Expr::In(field, self.list(field)?));
+        }
+
+        if self.keyword("not") {
+            if !self.keyword("in") {
+                return self.error(format!("expected in, {}", self.found()));
+            }
+
+            return Ok(Expr::Not(Box::new(Expr::In(field, self.list(field)?))));
+        }
+
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            _ => return self.error(format!("expected an operator after {}, {}", field, self.found()))
+        };
+
+        self.position += 1;
+
+        if op == "~" || op == "!~" {
+            let pattern = match self.peek() {
+                Some(Token::Text(text)) | Some(Token::Word(text)) => text.clone(),
+                _ => return self.error(format!("expected a pattern, {}", self.found()))
+            };
+            let regex = RegexBuilder::new(&pattern)
+                .case_insensitive(true)
+                .build();
+            let regex = match regex {
+                Ok(regex) => regex,
+                Err(err) => return self.error(format!("invalid pattern: {}", err))
+            };
+
+            self.position += 1;
+
+            let expr = Expr::Matches(field, regex);
+
+            return Ok(if op == "!~" { Expr::Not(Box::new(expr)) } else { expr });
+        }
+
+        let literal = self.literal(field)?;
+
+        Ok(match op {
+            "!=" => Expr::Not(Box::new(Expr::Compare(field, Op::Eq, literal))),
+            "<" => Expr::Compare(field, Op::Lt, literal),
+            "<=" => Expr::Compare(field, Op::Le, literal),
+            ">" => Expr::Compare(field, Op::Gt, literal),
+            ">=" => Expr::Compare(field, Op::Ge, literal),
+            _ => Expr::Compare(field, Op::Eq, literal)
+        })
+    }
+}
+
+/// # `Query` represents a filter on hardware revisions, ex:
+/// `brand ~ "tp-link" and release >= 19.07 and hw_version in ["v4", "v5"]`
+///
+/// Comparisons are `field op value`, with `=`, `!=`, `<`, `<=`, `>`, `>=`, `~` & `!~` for
+/// regular expressions, or `field in [value, ...]`, combined with `and`, `or`, `not` & parentheses.
+/// Text is compared ignoring case, releases in release order, numbers as numbers. Comparisons
+/// on unknown values don't hold.
+#[derive(Debug)]
+pub struct Query {
+    expr: Expr
+}
+
+impl Query {
+    /// Returns whether `revision` of `model` by `brand` matches
+    pub fn matches(&self, brand: &str, model: &str, revision: &Revision) -> bool {
+        self.expr.eval(brand, model, revision)
+    }
+
+    /// Returns whether any compared field comes from technical data, see `Hardware`
+    pub fn is_technical(&self) -> bool {
+        self.expr.is_technical()
+    }
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(text)?, position: 0, end: text.chars().count() + 1 };
+        let expr = parser.or()?;
+
+        if parser.position < parser.tokens.len() {
+            return parser.error(format!("expected and, or or the end of the query, {}", parser.found()));
+        }
+
+        Ok(Self { expr })
+    }
+}
+
+/// Keeps only hardware revisions of `manufacturers` matching `query`, models & brands left
+/// without any are removed
+pub fn filter(manufacturers: &mut Vec<Manufacturer>, query: &Query) {
+    for manufacturer in manufacturers.iter_mut() {
+        let brand = manufacturer.name().to_string();
+
+        for model in manufacturer.models_mut() {
+            let name = model.name().clone();
+
+            model.revisions_mut().retain(|revision| query.matches(&brand, &name, revision));
+        }
+
+        manufacturer.models_mut().retain(|model| !model.revisions().is_empty());
+    }
+
+    manufacturers.retain(|manufacturer| manufacturer.count() > 0);
+}
+
+/// # `Match` represents a hardware revision matching a query
+#[derive(Serialize, Debug)]
+pub struct Match {
+    brand: String,
+    model: String,
+    versions: String,
+    release: String
+}
+
+/// # `Group` represents matching hardware revisions sharing a value of the grouped by field
+///
+/// `value` - value of the field, `None` when unknown
+/// `count` - number of hardware revisions
+/// `models` - the hardware revisions, left out when only counting
+#[derive(Serialize, Debug)]
+pub struct Group {
+    value: Option<String>,
+    count: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    models: Vec<Match>
+}
+
+/// Groups hardware revisions of `manufacturers` by their value of `field`, ordered by it,
+/// with unknown values last, hardware revisions are left out with `count_only`
+pub fn group(manufacturers: &[Manufacturer], field: Field, count_only: bool) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+
+    for manufacturer in manufacturers {
+        for model in manufacturer.models() {
+            for revision in model.revisions() {
+                let values = field.values(manufacturer.name(), model.name(), revision);
+                let value = Some(values.join(", ")).filter(|value| !value.is_empty());
+
+                let index = match groups.iter().position(|group| group.value == value) {
+                    Some(index) => index,
+                    None => {
+                        groups.push(Group { value, count: 0, models: Vec::new() });
+                        groups.len() - 1
+                    }
+                };
+
+                groups[index].count += 1;
+
+                if !count_only {
+                    groups[index].models.push(Match {
+                        brand: manufacturer.name().to_string(),
+                        model: model.name().clone(),
+                        versions: revision.versions_text(),
+                        release: revision.openwrt_version().version().clone()
+                    });
+                }
+            }
+        }
+    }
+
+    groups.sort_by(|this, that| match (&this.value, &that.value) {
+        (Some(this), Some(that)) => field.cmp_values(this, that).then_with(|| this.cmp(that)),
+        (this, that) => that.is_some().cmp(&this.is_some())
+    });
+
+    groups
+}
+
+/// Prints `groups` with their hardware revisions, or as a count per group when they were left out
+pub fn print_table(groups: &[Group]) {
+    let width = groups.iter()
+        .map(|group| group.value.as_deref().unwrap_or("unknown").chars().count())
+        .max()
+        .unwrap_or_default();
+
+    for group in groups {
+        let value = group.value.as_deref().unwrap_or("unknown");
+
+        if group.models.is_empty() {
+            println!("{0:1$}  {2}", value.bold().bright_cyan(), width, group.count);
+            continue;
+        }
+
+        println!("{} - {} hardware revision(s)", value.bold().bright_cyan(), group.count);
+
+        for model in &group.models {
+            let versions = if model.versions.is_empty() { String::new() } else { format!(" {}", model.versions) };
+
+            println!("  {} {}{} - {}", model.brand.bold(), model.model, versions, model.release);
+        }
+    }
+}
+
+/// Prints `groups` as pretty printed JSON
+pub fn print_json(groups: &[Group]) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(groups)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::manufacturers;
+
+    fn models(query: &str) -> Vec<String> {
+        let mut manufacturers = manufacturers();
+
+        filter(&mut manufacturers, &query.parse().unwrap());
+
+        manufacturers.iter()
+            .flat_map(|manufacturer| manufacturer.models())
+            .flat_map(|model| model.revisions().iter().map(move |revision| format!("{} {}", model.name(), revision.versions_text())))
+            .map(|model| model.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn filters_by_query() {
+        assert_eq!(vec!["Archer C7 v5"], models(r#"brand ~ "tp-link" and release >= 19.07 and hw_version in ["v4","v5"]"#));
+        assert_eq!(vec!["R7800", "WNDR3700/WNDR3800 v1", "Archer C7 v2, v3", "Archer C7 v5"], models("release = 19.07"));
+        assert_eq!(vec!["Archer C7 v1", "Archer C7 AC1750 v4", "TL-WR703N", "TL-WR841N v9"], models("not release >= 19.07"));
+        assert_eq!(vec!["R7800", "TL-WR703N", "TL-WR841N v9"], models("(model ~ '^tl-' or ram_mb > 256) and hw_version != v1"));
+        assert_eq!(vec!["Archer C7 v2, v3", "Archer C7 v5"], models("model = 'ARCHER C7' and brand !~ netgear and release > 18"));
+        assert!(models("ram_mb > 256 and brand = tp-link").is_empty());
+    }
+
+    #[test]
+    fn reports_invalid_queries() {
+        let error = |query: &str| query.parse::<Query>().unwrap_err();
+
+        assert!(error("colour = red").starts_with("Invalid query at 1: Unknown field: colour, expected one of brand, model"));
+        assert_eq!("Invalid query at 11: release is a release, ex: 19.07, got latest", error("release > latest"));
+        assert_eq!("Invalid query at 17: expected and, or or the end of the query, found v4", error("hw_version = v5 v4"));
+        assert_eq!("Invalid query at 17: expected ), found the end of the query", error("(brand = tp-link"));
+        assert_eq!("Invalid query at 9: text isn't closed with \"", error(r#"brand = "tp-link"#));
+        assert_eq!("Invalid query at 7: unknown operator !", error("brand ! tp-link"));
+    }
+
+    #[test]
+    fn groups_by_field_in_field_order() {
+        let mut manufacturers = manufacturers();
+
+        filter(&mut manufacturers, &"release > 0".parse().unwrap());
+
+        let groups = group(&manufacturers, Field::Release, true);
+        let counts = groups.iter()
+            .map(|group| (group.value.as_deref().unwrap_or_default(), group.count, group.models.len()))
+            .collect::<Vec<(&str, usize, usize)>>();
+        assert_eq!(vec![("17.01.7", 1, 0), ("18.06.8", 2, 0), ("19.07.3", 4, 0)], counts);
+
+        let groups = group(&manufacturers, Field::FlashMb, false);
+        let values = groups.iter()
+            .map(|group| group.value.as_deref())
+            .collect::<Vec<Option<&str>>>();
+        assert_eq!(vec![Some("4"), Some("8"), Some("16"), Some("128"), None], values);
+        assert_eq!((1, 3), (groups[3].models.len(), groups[4].count));
+    }
+}
@@ -0,0 +1,269 @@
+use std::cmp::Reverse;
+use std::error::Error;
+use std::fs::File;
+use colored::*;
+use serde::{Serialize, Deserialize};
+
+use super::data::{Hardware, Manufacturer, ReleaseNumber, Resources};
+
+/// # `Requirements` represents what a recommended model must have, all optional
+///
+/// `release` - oldest acceptable supported release, ex: 19.07, or `latest` for the newest
+///             release branch in the catalog, release candidates aside
+/// `min_flash_mb` - minimum flash size in MB
+/// `min_ram_mb` - minimum RAM size in MB
+/// `wifi_5ghz` - whether 5 GHz Wi-Fi is needed
+/// `lan_ports` - minimum number of LAN ports, one ethernet port is taken as WAN
+///
+/// ## Example
+///
+/// { "release": "latest", "min_ram_mb": 128, "wifi_5ghz": true, "lan_ports": 4 }
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Requirements {
+    #[serde(default)]
+    pub release: Option<String>,
+    #[serde(default)]
+    pub min_flash_mb: Option<u32>,
+    #[serde(default)]
+    pub min_ram_mb: Option<u32>,
+    #[serde(default)]
+    pub wifi_5ghz: bool,
+    #[serde(default)]
+    pub lan_ports: Option<u32>
+}
+
+impl Requirements {
+    /// Loads requirements from JSON file at `file`
+    pub fn load(file: &str) -> Result<Self, Box<dyn Error>> {
+        let reader = File::open(shellexpand::tilde(file).trim())
+            .map_err(|err| format!("{}: {}", file, err))?;
+
+        Ok(serde_json::from_reader(reader).map_err(|err| format!("{}: Invalid requirements: {}", file, err))?)
+    }
+
+    /// Returns these requirements, with the ones set in `other` taking precedence
+    pub fn merge(self, other: Requirements) -> Self {
+        Self {
+            release: other.release.or(self.release),
+            min_flash_mb: other.min_flash_mb.or(self.min_flash_mb),
+            min_ram_mb: other.min_ram_mb.or(self.min_ram_mb),
+            wifi_5ghz: other.wifi_5ghz || self.wifi_5ghz,
+            lan_ports: other.lan_ports.or(self.lan_ports)
+        }
+    }
+
+    /// Returns whether any requirement needs technical data, see `Hardware`
+    pub fn needs_hardware(&self) -> bool {
+        self.min_flash_mb.is_some() || self.min_ram_mb.is_some() || self.wifi_5ghz || self.lan_ports.is_some()
+    }
+
+    /// Returns the oldest acceptable release, `latest` being the newest branch with a final release
+    /// among `manufacturers`, release candidates don't count
+    fn min_release(&self, manufacturers: &[Manufacturer]) -> Result<Option<(ReleaseNumber, bool)>, String> {
+        match self.release.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(release) if release.eq_ignore_ascii_case("latest") => Ok(manufacturers.iter()
+                .flat_map(|manufacturer| manufacturer.models())
+                .flat_map(|model| model.revisions())
+                .filter_map(|revision| revision.openwrt_version().release())
+                .filter(|release| !release.is_pre_release())
+                .max()
+                .and_then(|latest| latest.branch().parse().ok())
+                .map(|branch| (branch, true))),
+            Some(release) => release.parse()
+                .map(|release| Some((release, false)))
+        }
+    }
+}
+
+/// # `Recommendation` represents a hardware revision meeting all requirements, with why it did
+#[derive(Serialize, Debug)]
+pub struct Recommendation {
+    brand: String,
+    model: String,
+    versions: String,
+    release: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hardware: Option<Hardware>,
+    reasons: Vec<String>
+}
+
+/// Returns why `hardware` meets the hardware `requirements`, `None` when it doesn't
+fn hardware_reasons(requirements: &Requirements, hardware: Option<&Hardware>) -> Option<Vec<String>> {
+    let mut reasons = Vec::new();
+    let minimum = Resources { flash_mb: requirements.min_flash_mb, ram_mb: requirements.min_ram_mb };
+
+    if !minimum.are_met_by(hardware) {
+        return None;
+    }
+
+    if let (Some(minimum), Some(size)) = (requirements.min_flash_mb, hardware.and_then(|hardware| hardware.flash_mb)) {
+        reasons.push(format!("{} MB flash, at least {} needed", size, minimum));
+    }
+
+    if let (Some(minimum), Some(size)) = (requirements.min_ram_mb, hardware.and_then(|hardware| hardware.ram_mb)) {
+        reasons.push(format!("{} MB RAM, at least {} needed", size, minimum));
+    }
+
+    if requirements.wifi_5ghz {
+        match hardware.map(|hardware| hardware.wlan_50ghz.as_str()).filter(|wlan| !wlan.is_empty()) {
+            Some(wlan) => reasons.push(format!("5 GHz Wi-Fi, {}", wlan)),
+            None => return None
+        }
+    }
+
+    if let Some(minimum) = requirements.lan_ports {
+        match hardware.and_then(|hardware| hardware.ethernet_ports()) {
+            Some(ports) if ports.saturating_sub(1) >= minimum => reasons.push(
+                format!("{} LAN port(s), {} ethernet port(s) with one as WAN, at least {} needed", ports - 1, ports, minimum)),
+            _ => return None
+        }
+    }
+
+    Some(reasons)
+}
+
+/// Returns hardware revisions of `manufacturers` meeting all `requirements`, best first
+///
+/// Newer supported releases rank first, ties are broken by more RAM, then more flash
+pub fn recommend(manufacturers: &[Manufacturer], requirements: &Requirements) -> Result<Vec<Recommendation>, Box<dyn Error>> {
+    let min_release = requirements.min_release(manufacturers)?;
+    let mut ranked = Vec::new();
+
+    for manufacturer in manufacturers {
+        for model in manufacturer.models() {
+            for revision in model.revisions() {
+                let release = revision.openwrt_version().release();
+                let mut reasons = Vec::new();
+
+                match (&min_release, &release) {
+                    (None, _) => (),
+                    (Some((minimum, latest)), Some(release)) if release >= minimum => reasons.push(if *latest {
+                        format!("supported on {}, the latest release branch", release)
+                    }
+                    else {
+                        format!("supported on {}, {} or newer needed", release, minimum)
+                    }),
+                    (Some(_), _) => continue
+                }
+
+                match hardware_reasons(requirements, revision.hardware()) {
+                    Some(hardware_reasons) => reasons.extend(hardware_reasons),
+                    None => continue
+                }
+
+                let hardware = revision.hardware();
+                let key = (
+                    release.clone(),
+                    hardware.and_then(|hardware| hardware.ram_mb),
+                    hardware.and_then(|hardware| hardware.flash_mb)
+                );
+
+                ranked.push((key, Recommendation {
+                    brand: manufacturer.name().to_string(),
+                    model: model.name().clone(),
+                    versions: revision.versions_text(),
+                    release: revision.openwrt_version().version().clone(),
+                    hardware: hardware.cloned(),
+                    reasons
+                }));
+            }
+        }
+    }
+
+    ranked.sort_by_key(|(key, _)| Reverse(key.clone()));
+
+    Ok(ranked.into_iter()
+        .map(|(_, recommendation)| recommendation)
+        .collect())
+}
+
+/// Prints `recommendations` ranked, each with why it qualified
+pub fn print_table(recommendations: &[Recommendation]) {
+    for (i, recommendation) in recommendations.iter().enumerate() {
+        let versions = if recommendation.versions.is_empty() { String::new() } else { format!(" {}", recommendation.versions) };
+        let resources = recommendation.hardware.as_ref()
+            .map(|hardware| format!(", {} MB", hardware.resources()))
+            .unwrap_or_default();
+
+        println!("{}. {} {}{} - {}{}",
+            i + 1,
+            recommendation.brand.bold(),
+            recommendation.model.bold(),
+            versions,
+            recommendation.release.bright_green(),
+            resources);
+
+        for reason in &recommendation.reasons {
+            println!("   {} {}", "✓".green(), reason);
+        }
+    }
+}
+
+/// Prints `recommendations` as pretty printed JSON
+pub fn print_json(recommendations: &[Recommendation]) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(recommendations)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::{manufacturers, manufacturers_on};
+
+    #[test]
+    fn ranks_models_meeting_requirements() {
+        let requirements = Requirements {
+            release: Some("latest".to_string()),
+            min_ram_mb: Some(128),
+            wifi_5ghz: true,
+            lan_ports: Some(4),
+            ..Requirements::default()
+        };
+        let recommendations = recommend(&manufacturers(), &requirements).unwrap();
+
+        let models = recommendations.iter()
+            .map(|recommendation| recommendation.model.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(vec!["R7800", "Archer C7"], models);
+
+        assert_eq!(vec![
+            "supported on 19.07.3, the latest release branch",
+            "128 MB RAM, at least 128 needed",
+            "5 GHz Wi-Fi, a/n/ac",
+            "4 LAN port(s), 5 ethernet port(s) with one as WAN, at least 4 needed"
+        ], recommendations[1].reasons);
+    }
+
+    #[test]
+    fn merges_requirements_preferring_flags() {
+        let file = serde_json::from_str::<Requirements>(r#"{ "release": "18.06", "min_ram_mb": 64, "wifi_5ghz": true }"#).unwrap();
+        let flags = Requirements { min_ram_mb: Some(128), ..Requirements::default() };
+
+        assert_eq!(Requirements {
+            release: Some("18.06".to_string()),
+            min_ram_mb: Some(128),
+            wifi_5ghz: true,
+            ..Requirements::default()
+        }, file.merge(flags));
+
+        assert!(serde_json::from_str::<Requirements>(r#"{ "ram": 64 }"#).is_err());
+
+        let recommendations = recommend(&manufacturers(), &Requirements { release: Some("17.01".to_string()), ..Requirements::default() }).unwrap();
+        assert_eq!(7, recommendations.len());
+        assert_eq!("17.01.7", recommendations[6].release);
+    }
+
+    #[test]
+    fn doesnt_take_release_candidates_as_latest() {
+        let requirements = Requirements { release: Some("latest".to_string()), ..Requirements::default() };
+        let recommendations = recommend(&manufacturers_on("21.02.0-rc1"), &requirements).unwrap();
+
+        let models = recommendations.iter()
+            .map(|recommendation| recommendation.model.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(vec!["Archer C7", "R7800", "WNDR3700/WNDR3800", "Archer C7"], models);
+    }
+}
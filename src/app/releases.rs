@@ -0,0 +1,215 @@
+use std::error::Error;
+use std::fs;
+use reqwest::{blocking, Url};
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use super::data::{Registry, Release, ReleaseNumber, Status};
+use super::{ODIN_RELEASES_PAGE, RELEASES_PAGE};
+
+/// Returns `token` as a release number, if it looks like one, ex: 19.07.3 but not 2020 or v2
+fn release_token(token: &str) -> Option<ReleaseNumber> {
+    let token = token.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+
+    if !token.starts_with(|c: char| c.is_ascii_digit()) || !token.contains('.') {
+        return None;
+    }
+
+    token.parse().ok()
+}
+
+/// Returns whether `token` is a date written as YYYY-MM-DD
+fn is_date(token: &str) -> bool {
+    token.len() == 10 && token.char_indices().all(|(i, c)| match i {
+        4 | 7 => c == '-',
+        _ => c.is_ascii_digit()
+    })
+}
+
+/// Returns whether `words` hold all words of `phrase` in a row
+fn contains_phrase(words: &[&str], phrase: &str) -> bool {
+    let phrase = phrase.split(' ')
+        .collect::<Vec<&str>>();
+
+    words.windows(phrase.len())
+        .any(|window| window == phrase.as_slice())
+}
+
+/// Returns the lifecycle status stated in `text`, if any, either as the whole text, ex: eol,
+/// or as whole words within it, ex: end of life 2020-12, but not geolocation
+fn stated_status(text: &str) -> Option<Status> {
+    if let Ok(status) = text.parse() {
+        return Some(status);
+    }
+
+    let text = text.to_lowercase();
+    let words = text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>();
+
+    if contains_phrase(&words, "end of life") || contains_phrase(&words, "eol") {
+        Some(Status::Eol)
+    }
+    else if contains_phrase(&words, "old stable") || contains_phrase(&words, "oldstable") {
+        Some(Status::OldStable)
+    }
+    else if contains_phrase(&words, "current stable") {
+        Some(Status::Current)
+    }
+    else {
+        None
+    }
+}
+
+/// Scrapes releases off OpenWrt's releases page, or a saved copy of it
+///
+/// Any table row holding a release number is a release, its date & status are
+/// taken from the other cells of the row when there are any
+pub fn parse_html(html_content: &str) -> Vec<Release> {
+    let document = Html::parse_document(html_content);
+    let tr_selector = Selector::parse("tr")
+        .expect("tr is a valid selector");
+    let cell_selector = Selector::parse("td, th")
+        .expect("td, th is a valid selector");
+
+    document.select(&tr_selector)
+        .filter_map(|row| {
+            let cells = row.select(&cell_selector)
+                .map(|cell| cell.text().collect::<Vec<&str>>().join(" "))
+                .collect::<Vec<String>>();
+            let version = cells.iter()
+                .find_map(|cell| cell.split_whitespace().find_map(release_token))?;
+            let date = cells.iter()
+                .flat_map(|cell| cell.split_whitespace())
+                .find(|token| is_date(token))
+                .unwrap_or_default();
+            let status = cells.iter()
+                .find_map(|cell| stated_status(cell));
+
+            Some(Release::new(version.to_string(), date.to_string(), status))
+        })
+        .collect()
+}
+
+/// Parses releases from JSON, a list of releases or an object with one under `releases`
+///
+/// ## Example
+///
+/// [{ "version": "19.07.3", "date": "2020-05-16" }, { "version": "18.06.8", "status": "eol" }]
+pub fn parse_json(json_content: &str) -> Result<Vec<Release>, Box<dyn Error>> {
+    let invalid = |err: serde_json::Error| format!("Invalid releases file: {}", err);
+    let mut releases: Value = serde_json::from_str(json_content)
+        .map_err(invalid)?;
+
+    if releases.is_object() {
+        releases = releases["releases"].take();
+    }
+
+    Ok(serde_json::from_value(releases).map_err(invalid)?)
+}
+
+/// Parses releases from `content`, either JSON or a releases page, told apart by content
+pub fn parse(content: &str) -> Result<Vec<Release>, Box<dyn Error>> {
+    match content.trim_start_matches('\u{feff}').trim_start().chars().next() {
+        Some('[') | Some('{') => parse_json(content),
+        _ => Ok(parse_html(content))
+    }
+}
+
+/// Loads html content of OpenWrt's releases page
+fn load_html_content_from_http() -> Result<String, Box<dyn Error>> {
+    let response = blocking::Client::new()
+        .get(Url::parse(RELEASES_PAGE)?)
+        .send()?;
+    let status_code = response.status();
+
+    debug!("{} answered {}", response.url(), status_code);
+
+    if status_code != 200 {
+        return Err(format!("Couldn't load releases, {} answered {}", RELEASES_PAGE, status_code).into());
+    }
+
+    Ok(response.text()?)
+}
+
+/// Loads the release registry from user given `file`, the one in odin home or the web
+///
+/// `file` may be a saved releases page or a JSON list of releases, see `parse`
+pub fn load_registry(file: Option<&str>) -> Result<Registry, Box<dyn Error>> {
+    let path = file.unwrap_or(ODIN_RELEASES_PAGE);
+
+    let releases = match fs::read_to_string(shellexpand::tilde(path).trim()) {
+        Ok(content) => {
+            info!("Loaded releases from file: {}", path);
+
+            parse(&content).map_err(|err| format!("{}: {}", path, err))?
+        },
+        Err(err) if file.is_some() => return Err(format!("{}: {}", path, err).into()),
+        Err(_) => {
+            info!("Loading releases from web");
+
+            parse_html(&load_html_content_from_http()?)
+        }
+    };
+
+    let registry = Registry::new(releases);
+
+    if registry.is_empty() {
+        return Err("Found 0 release(s)!".into());
+    }
+
+    debug!("Found {} release branch(es)", registry.branches().len());
+
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrapes_releases_page() {
+        let releases = parse(r#"
+            <html><body>
+                <table>
+                    <tr><th>Release</th><th>Date</th><th>Notes</th></tr>
+                    <tr><td><a href="/releases/19.07/notes-19.07.3">OpenWrt 19.07.3</a></td><td>2020-05-16</td><td>current stable</td></tr>
+                    <tr><td>18.06.8</td><td>2020-02-27</td><td>end of life 2020-12</td></tr>
+                    <tr><td>v2 hardware</td><td>2019</td></tr>
+                </table>
+            </body></html>
+        "#).unwrap();
+
+        let releases = releases.iter()
+            .map(|release| (release.version().as_str(), release.date().as_str()))
+            .collect::<Vec<(&str, &str)>>();
+
+        assert_eq!(vec![("19.07.3", "2020-05-16"), ("18.06.8", "2020-02-27")], releases);
+    }
+
+    #[test]
+    fn reads_stated_status_from_whole_words() {
+        assert_eq!(Some(Status::Eol), stated_status("EOL"));
+        assert_eq!(Some(Status::Eol), stated_status("end-of-life 2020-12"));
+        assert_eq!(Some(Status::Eol), stated_status("(eol)"));
+        assert_eq!(Some(Status::OldStable), stated_status("old stable"));
+        assert_eq!(Some(Status::Current), stated_status("current stable release"));
+        assert_eq!(None, stated_status("fixes geolocation & people counting"));
+        assert_eq!(None, stated_status("Stable releases"));
+    }
+
+    #[test]
+    fn parses_release_lists() {
+        let registry = Registry::new(parse(r#"{ "releases": [
+            { "version": "19.07.3", "date": "2020-05-16" },
+            { "version": "18.06.8", "status": "eol" }
+        ] }"#).unwrap());
+
+        let branches = registry.branches();
+        assert_eq!(Status::Current, branches[0].status);
+        assert_eq!(Status::Eol, branches[1].status);
+
+        let err = parse(r#"[{ "date": "2020-05-16" }]"#).unwrap_err();
+        assert_eq!("Invalid releases file: missing field `version`", err.to_string());
+    }
+}
@@ -0,0 +1,215 @@
+use std::error::Error;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::data::Manufacturer;
+
+/// Version of the JSON catalog format, bumped on every breaking change to it,
+/// like renaming or removing a field or changing its type
+///
+/// Adding optional fields isn't a breaking change, consumers should ignore fields they don't know
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// # `Catalog` represents the JSON catalog document odin writes
+///
+/// `schema_version` - version of the format, see `SCHEMA_VERSION`
+/// `brands` - all listed brands, with their models & hardware revisions
+///
+/// ## Example
+///
+/// { "schema_version": 1, "brands": [{ "name": "TP-Link", "models": [...] }] }
+#[derive(Serialize)]
+pub struct Catalog<'m> {
+    schema_version: u32,
+    brands: &'m [&'m Manufacturer]
+}
+
+impl<'m> Catalog<'m> {
+    /// Creates a new instance of `Catalog`, at the current `SCHEMA_VERSION`
+    pub fn new(brands: &'m [&'m Manufacturer]) -> Self {
+        Self { schema_version: SCHEMA_VERSION, brands }
+    }
+
+    /// Returns pretty printed JSON of this catalog
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Returns the JSON Schema document describing the catalog written by `Catalog`
+///
+/// Brands, models, revisions & versions are described in `$defs`, other JSON outputs
+/// like the ones of `odin serve` use the same objects
+pub fn document() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("urn:odin:catalog:v{}", SCHEMA_VERSION),
+        "title": "ODIN catalog",
+        "description": "Brands of OpenWrt's supported devices page, with their models & hardware revisions",
+        "type": "object",
+        "required": ["schema_version", "brands"],
+        "properties": {
+            "schema_version": {
+                "description": "Version of this format, bumped on every breaking change",
+                "const": SCHEMA_VERSION
+            },
+            "brands": {
+                "type": "array",
+                "items": { "$ref": "#/$defs/brand" }
+            }
+        },
+        "$defs": {
+            "brand": {
+                "type": "object",
+                "required": ["name", "models"],
+                "properties": {
+                    "name": { "type": "string", "description": "Name of the brand, ex: TP-Link" },
+                    "models": { "type": "array", "items": { "$ref": "#/$defs/model" } }
+                }
+            },
+            "model": {
+                "type": "object",
+                "required": ["name", "revisions"],
+                "properties": {
+                    "name": { "type": "string", "description": "Name of the model, ex: Archer C7" },
+                    "revisions": { "type": "array", "items": { "$ref": "#/$defs/revision" } }
+                }
+            },
+            "revision": {
+                "type": "object",
+                "required": ["versions", "openwrt_version"],
+                "properties": {
+                    "versions": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Normalized hardware versions, ex: v2"
+                    },
+                    "versions_text": { "type": "string", "description": "Hardware versions as written on the devices page" },
+                    "openwrt_version": { "$ref": "#/$defs/version" },
+                    "device_page": { "type": "string", "description": "Link to the device page, empty when there's none" },
+                    "hardware": { "$ref": "#/$defs/hardware" }
+                }
+            },
+            "hardware": {
+                "type": "object",
+                "description": "Technical data from the ToH dump or device pages, only known fields are written",
+                "required": [],
+                "properties": {
+                    "soc": { "type": "string", "description": "System on chip, ex: Qualcomm Atheros QCA9558" },
+                    "flash_mb": { "type": "integer", "description": "Flash size in MB, the smallest one when variants differ" },
+                    "ram_mb": { "type": "integer", "description": "RAM size in MB, the smallest one when variants differ" },
+                    "wlan_24ghz": { "type": "string", "description": "2.4 GHz Wi-Fi standards, ex: b/g/n" },
+                    "wlan_50ghz": { "type": "string", "description": "5 GHz Wi-Fi standards, ex: a/n/ac" },
+                    "ethernet_100m_ports": { "type": "integer", "description": "Number of 100 Mbit ethernet ports, WAN included" },
+                    "ethernet_1g_ports": { "type": "integer", "description": "Number of gigabit ethernet ports, WAN included" }
+                }
+            },
+            "version": {
+                "type": "object",
+                "required": ["version"],
+                "properties": {
+                    "version": { "type": "string", "description": "Supported OpenWrt release, empty when there's none" },
+                    "link": { "type": "string", "description": "Link to the release page, empty when there's none" }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::Hardware;
+    use super::super::data::fixtures::{self, model, revision_with_page};
+
+    /// Checks that `value` has exactly the properties `def` allows & all the ones it requires,
+    /// following `$ref`s into nested objects & arrays
+    fn assert_matches(schema: &Value, def: &Value, value: &Value, path: &str) {
+        let def = match def["$ref"].as_str() {
+            Some(reference) => &schema["$defs"][reference.trim_start_matches("#/$defs/")],
+            None => def
+        };
+
+        match def["type"].as_str() {
+            Some("object") => {
+                let object = value.as_object().unwrap_or_else(|| panic!("Expected an object at {}", path));
+                let properties = def["properties"].as_object().unwrap();
+
+                for key in def["required"].as_array().unwrap() {
+                    assert!(object.contains_key(key.as_str().unwrap()), "Expected {} at {}", key, path);
+                }
+
+                for (key, value) in object {
+                    let property = properties.get(key)
+                        .unwrap_or_else(|| panic!("Unexpected {} at {}, not in the schema", key, path));
+
+                    assert_matches(schema, property, value, &format!("{}.{}", path, key));
+                }
+            },
+            Some("array") => {
+                for (i, item) in value.as_array().unwrap().iter().enumerate() {
+                    assert_matches(schema, &def["items"], item, &format!("{}[{}]", path, i));
+                }
+            },
+            Some("string") => assert!(value.is_string(), "Expected a string at {}", path),
+            Some("integer") => assert!(value.is_u64(), "Expected an integer at {}", path),
+            _ => assert_eq!(def["const"], *value, "Unexpected value at {}", path)
+        }
+    }
+
+    #[test]
+    fn writes_catalog_in_pinned_format() {
+        let manufacturers = [
+            Manufacturer::new("TP-Link".to_string(), vec![
+                model("Archer C7", vec![
+                    revision_with_page(&["v2"], "19.07.3", "https://openwrt.org/toh/tp-link/archer_c7")
+                        .with_versions_text("V2".to_string())
+                ])
+            ])
+        ];
+        let brands = manufacturers.iter().collect::<Vec<&Manufacturer>>();
+        let catalog: Value = serde_json::from_str(&Catalog::new(&brands).to_json().unwrap()).unwrap();
+
+        // changing this is a breaking change for consumers, bump `SCHEMA_VERSION` along with it
+        assert_eq!(json!({
+            "schema_version": 1,
+            "brands": [{
+                "name": "TP-Link",
+                "models": [{
+                    "name": "Archer C7",
+                    "revisions": [{
+                        "versions": ["v2"],
+                        "versions_text": "V2",
+                        "openwrt_version": {
+                            "version": "19.07.3",
+                            "link": "https://openwrt.org/releases/19.07.3"
+                        },
+                        "device_page": "https://openwrt.org/toh/tp-link/archer_c7"
+                    }]
+                }]
+            }]
+        }), catalog);
+    }
+
+    #[test]
+    fn schema_describes_written_catalog() {
+        let mut manufacturers = fixtures::manufacturers();
+        manufacturers[1].models_mut()[0].revisions_mut()[1].set_hardware(Hardware {
+            soc: "Qualcomm Atheros QCA9558".to_string(),
+            flash_mb: Some(16),
+            ram_mb: Some(128),
+            wlan_24ghz: "b/g/n".to_string(),
+            wlan_50ghz: "a/n/ac".to_string(),
+            ethernet_100m_ports: None,
+            ethernet_1g_ports: Some(5)
+        });
+
+        let brands = manufacturers.iter().collect::<Vec<&Manufacturer>>();
+        let catalog: Value = serde_json::from_str(&Catalog::new(&brands).to_json().unwrap()).unwrap();
+        let schema = document();
+
+        assert_eq!(json!(SCHEMA_VERSION), schema["properties"]["schema_version"]["const"]);
+        assert_eq!(json!(format!("urn:odin:catalog:v{}", SCHEMA_VERSION)), schema["$id"]);
+        assert_matches(&schema, &schema, &catalog, "$");
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use serde_json::json;
+use tiny_http::{Header, Server};
+
+use super::data::{self, Manufacturer};
+
+/// # `Catalog` represents the data served, along with when it was loaded
+///
+/// `manufacturers` - all brands, as last loaded successfully
+/// `loaded_at` - when `manufacturers` were loaded
+/// `error` - error of the last refresh, if it failed
+pub struct Catalog {
+    manufacturers: Vec<Manufacturer>,
+    loaded_at: SystemTime,
+    error: Option<String>
+}
+
+impl Catalog {
+    pub fn new(manufacturers: Vec<Manufacturer>) -> Self {
+        Self { manufacturers, loaded_at: SystemTime::now(), error: None }
+    }
+}
+
+/// # `Response` represents a JSON response, before it's written out
+///
+/// `status` - http status code
+/// `body` - JSON body, empty for `304 Not Modified`
+/// `etag` - entity tag of the body
+#[derive(Debug)]
+pub struct Response {
+    status: u16,
+    body: String,
+    etag: Option<String>
+}
+
+impl Response {
+    fn json<T: Serialize>(val: &T) -> Self {
+        let body = serde_json::to_string_pretty(val)
+            .unwrap_or_else(|err| json!({ "error": err.to_string() }).to_string());
+        let mut hasher = DefaultHasher::new();
+
+        body.hash(&mut hasher);
+
+        Self { status: 200, body, etag: Some(format!("\"{:016x}\"", hasher.finish())) }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self { status, body: json!({ "error": message }).to_string(), etag: None }
+    }
+}
+
+/// Brand with its number of models, as listed by `/brands`
+#[derive(Serialize)]
+struct BrandSummary<'m> {
+    name: &'m str,
+    models: usize
+}
+
+/// Model along with its brand, as listed by `/search`
+#[derive(Serialize)]
+struct SearchResult<'m> {
+    brand: &'m str,
+    #[serde(flatten)]
+    model: &'m data::Model
+}
+
+/// Splits `path_and_query` into decoded path segments & query parameters
+fn parse_path(path_and_query: &str) -> (Vec<String>, BTreeMap<String, String>) {
+    let (path, query) = path_and_query.split_once('?')
+        .unwrap_or((path_and_query, ""));
+    let decode = |val: &str| percent_decode_str(&val.replace('+', " "))
+        .decode_utf8_lossy()
+        .to_string();
+    let segments = path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(decode)
+        .collect();
+    let params = query.split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (key, val) = param.split_once('=').unwrap_or((param, ""));
+
+            (decode(key), decode(val))
+        })
+        .collect();
+
+    (segments, params)
+}
+
+/// Answers a `GET` for `path_and_query` from `catalog`
+///
+/// ## Endpoints
+///
+/// 1. `/health` - data freshness
+/// 2. `/brands` - all brands, with their number of models
+/// 3. `/brands/<brand>` - a brand, with all its models
+/// 4. `/brands/<brand>/models/<model>?version=<version>` - a model, only its revision with `version` when given
+/// 5. `/search?q=<text>` - models whose brand or name contains `text`
+/// 6. `/stats` - number of brands & models, hardware revisions per supported release
+pub fn route(catalog: &Catalog, path_and_query: &str) -> Response {
+    let (segments, params) = parse_path(path_and_query);
+    let segments = segments.iter()
+        .map(|segment| segment.as_str())
+        .collect::<Vec<&str>>();
+    let manufacturers = &catalog.manufacturers;
+
+    match segments.as_slice() {
+        ["health"] => {
+            let loaded_at = catalog.loaded_at
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            let age = catalog.loaded_at
+                .elapsed()
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+
+            Response::json(&json!({
+                "status": if catalog.error.is_some() { "stale" } else { "ok" },
+                "loaded_at": loaded_at,
+                "age_seconds": age,
+                "brands": manufacturers.len(),
+                "error": catalog.error
+            }))
+        },
+        ["brands"] => Response::json(&manufacturers.iter()
+            .map(|manufacturer| BrandSummary { name: manufacturer.name(), models: manufacturer.count() })
+            .collect::<Vec<BrandSummary>>()),
+        ["brands", brand] => match data::find_manufacturer(manufacturers, brand) {
+            Some(manufacturer) => Response::json(manufacturer),
+            None => Response::error(404, &format!("Found 0 brand(s) for {}!", brand))
+        },
+        ["brands", brand, "models", model] => {
+            let model = data::find_manufacturer(manufacturers, brand)
+                .and_then(|manufacturer| manufacturer.find_model(model));
+
+            match (model, params.get("version")) {
+                (Some(model), Some(version)) => match model.find_revision(Some(version)) {
+                    Some(revision) => Response::json(&json!({ "name": model.name(), "revisions": [revision] })),
+                    None => Response::error(404, &format!("Found 0 revision(s) for {}!", version))
+                },
+                (Some(model), None) => Response::json(model),
+                (None, _) => Response::error(404, "Found 0 model(s)!")
+            }
+        },
+        ["search"] => {
+            let text = data::normalize(params.get("q").map(|q| q.as_str()).unwrap_or_default());
+            let text = text.as_str();
+            let results = manufacturers.iter()
+                .flat_map(|manufacturer| {
+                    let is_brand = data::normalize(manufacturer.name()).contains(text);
+
+                    manufacturer.models()
+                        .iter()
+                        .filter(move |model| is_brand || data::normalize(model.name()).contains(text))
+                        .map(move |model| SearchResult { brand: manufacturer.name(), model })
+                })
+                .collect::<Vec<SearchResult>>();
+
+            Response::json(&results)
+        },
+        ["stats"] => {
+            let mut releases: BTreeMap<String, usize> = BTreeMap::new();
+
+            let revisions = manufacturers.iter()
+                .flat_map(|manufacturer| manufacturer.models())
+                .flat_map(|model| model.revisions());
+
+            for revision in revisions {
+                let branch = revision.openwrt_version()
+                    .release()
+                    .map(|release| release.branch())
+                    .unwrap_or_else(|| "N/A".to_string());
+
+                *releases.entry(branch).or_default() += 1;
+            }
+
+            Response::json(&json!({
+                "brands": manufacturers.len(),
+                "models": manufacturers.iter().map(|manufacturer| manufacturer.count()).sum::<usize>(),
+                "releases": releases
+            }))
+        },
+        _ => Response::error(404, &format!("Not found: {}", path_and_query))
+    }
+}
+
+/// Reloads catalog with `load`, every `refresh`
+/// A failed reload keeps serving the previous data, noting the error for `/health`
+fn refresh_periodically<F>(catalog: Arc<RwLock<Catalog>>, load: F, refresh: Duration)
+    where F: Fn() -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> {
+    loop {
+        thread::sleep(refresh);
+
+        let result = load()
+            .map_err(|err| err.to_string())
+            .and_then(|manufacturers| manufacturers.ok_or_else(|| "Found 0 brand(s)!".to_string()));
+
+        if let Ok(mut catalog) = catalog.write() {
+            match result {
+                Ok(manufacturers) => {
+                    debug!("Refreshed catalog, {} brand(s)", manufacturers.len());
+                    *catalog = Catalog::new(manufacturers);
+                },
+                Err(err) => {
+                    error!("Error refreshing catalog: {}", err);
+                    catalog.error = Some(err);
+                }
+            }
+        }
+    }
+}
+
+/// Whether `if_none_match` header matches `etag`, either as `*` or as one of its comma separated
+/// entity tags, weak ones included as `If-None-Match` compares them weakly
+fn matches_etag(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+
+    if_none_match.trim() == "*" || if_none_match.split(',')
+        .map(|tag| tag.trim())
+        .any(|tag| tag.trim_start_matches("W/") == etag)
+}
+
+/// Handles requests to `server` from `catalog`, until the server is dropped
+fn handle_requests(server: Server, catalog: Arc<RwLock<Catalog>>) {
+    for request in server.incoming_requests() {
+        let response = if request.method() != &tiny_http::Method::Get {
+            Response::error(405, "Only GET is supported!")
+        }
+        else {
+            match catalog.read() {
+                Ok(catalog) => route(&catalog, request.url()),
+                Err(_) => Response::error(500, "Catalog unavailable!")
+            }
+        };
+        let not_modified = response.etag.as_deref().is_some_and(|etag| request.headers()
+            .iter()
+            .filter(|header| header.field.equiv("If-None-Match"))
+            .any(|header| matches_etag(header.value.as_str(), etag)));
+        let (status, body) = if not_modified {
+            (304, String::new())
+        }
+        else {
+            (response.status, response.body)
+        };
+
+        trace!("{} {} -> {}", request.method(), request.url(), status);
+        let mut http_response = tiny_http::Response::from_string(body)
+            .with_status_code(status);
+
+        for (field, value) in [("Content-Type", Some("application/json")), ("ETag", response.etag.as_deref())] {
+            if let Some(header) = value.and_then(|value| Header::from_bytes(field, value).ok()) {
+                http_response.add_header(header);
+            }
+        }
+
+        let _ = request.respond(http_response);
+    }
+}
+
+/// Starts serving `manufacturers` on localhost `port` in the background, reloading them with
+/// `load` every `refresh`, never when it's `None`
+///
+/// Returns the address being listened on, useful with port `0`
+pub fn spawn<F>(manufacturers: Vec<Manufacturer>, load: F, port: u16, refresh: Option<Duration>) -> Result<SocketAddr, Box<dyn Error>>
+    where F: Fn() -> Result<Option<Vec<Manufacturer>>, Box<dyn Error>> + Send + 'static {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|err| format!("Error listening on port {}: {}", port, err))?;
+    let addr = server.server_addr()
+        .to_ip()
+        .ok_or("Error listening on localhost")?;
+    let catalog = Arc::new(RwLock::new(Catalog::new(manufacturers)));
+
+    if let Some(refresh) = refresh {
+        let refresh_catalog = Arc::clone(&catalog);
+
+        thread::spawn(move || refresh_periodically(refresh_catalog, load, refresh));
+    }
+
+    thread::spawn(move || handle_requests(server, catalog));
+
+    Ok(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{blocking, StatusCode};
+    use super::super::data::fixtures::manufacturers;
+
+    fn catalog() -> Catalog {
+        Catalog::new(manufacturers())
+    }
+
+    fn body(response: Response) -> serde_json::Value {
+        assert_eq!(200, response.status, "Expected 200, but found {}: {}", response.status, response.body);
+
+        serde_json::from_str(&response.body).unwrap()
+    }
+
+    #[test]
+    fn routes_catalog_endpoints() {
+        let catalog = catalog();
+
+        assert_eq!(json!([{ "name": "Netgear", "models": 2 }, { "name": "TP-Link", "models": 4 }]), body(route(&catalog, "/brands")));
+        assert_eq!("TP-Link", body(route(&catalog, "/brands/tp-link"))["name"]);
+        assert_eq!(3, body(route(&catalog, "/brands/TP-Link/models/archer%20c7"))["revisions"]
+            .as_array()
+            .map(|revisions| revisions.len())
+            .unwrap_or_default());
+
+        let model = body(route(&catalog, "/brands/TP-Link/models/archer%20c7?version=V2"));
+        assert_eq!("Archer C7", model["name"]);
+        assert_eq!(json!(["v2", "v3"]), model["revisions"][0]["versions"]);
+        assert_eq!(2, body(route(&catalog, "/search?q=archer+c"))
+            .as_array()
+            .map(|results| results.len())
+            .unwrap_or_default());
+        assert_eq!(json!({ "17.01": 1, "18.06": 2, "19.07": 4, "N/A": 1 }), body(route(&catalog, "/stats"))["releases"]);
+        assert_eq!("ok", body(route(&catalog, "/health"))["status"]);
+
+        assert_eq!(404, route(&catalog, "/brands/linksys").status);
+        assert_eq!(404, route(&catalog, "/brands/tp-link/models/archer%20c8").status);
+        assert_eq!(404, route(&catalog, "/brands/tp-link/models/archer%20c7?version=v9").status);
+        assert_eq!(404, route(&catalog, "/unknown").status);
+    }
+
+    #[test]
+    fn serves_catalog_with_etags() {
+        let addr = spawn(catalog().manufacturers, || Ok(None), 0, None)
+            .unwrap();
+        let client = blocking::Client::new();
+        let url = format!("http://{}/brands", addr);
+
+        let response = client.get(&url)
+            .send()
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+
+        let etag = response.headers()
+            .get("ETag")
+            .map(|etag| etag.to_str().unwrap().to_string());
+        assert!(etag.is_some(), "Expected an ETag, but found none");
+
+        let response = client.get(&url)
+            .header("If-None-Match", etag.unwrap())
+            .send()
+            .unwrap();
+        assert_eq!(StatusCode::NOT_MODIFIED, response.status());
+
+        let response = client.get(&url)
+            .header("If-None-Match", "\"0000000000000000\"")
+            .send()
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+
+        let response = client.get(&format!("http://{}/health", addr))
+            .send()
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[test]
+    fn matches_etags() {
+        assert!(matches_etag("\"abc\"", "\"abc\""));
+        assert!(matches_etag("*", "\"abc\""));
+        assert!(matches_etag("\"xyz\", \"abc\"", "\"abc\""));
+        assert!(matches_etag("W/\"abc\"", "\"abc\""));
+        assert!(matches_etag(" \"xyz\",W/\"abc\" ", "\"abc\""));
+
+        assert!(!matches_etag("\"xyz\"", "\"abc\""));
+        assert!(!matches_etag("abc", "\"abc\""));
+        assert!(!matches_etag("", "\"abc\""));
+    }
+
+    #[test]
+    fn refreshes_catalog_with_loader() {
+        let catalog = Arc::new(RwLock::new(Catalog::new(vec![])));
+        let refresh_catalog = Arc::clone(&catalog);
+
+        thread::spawn(move || refresh_periodically(refresh_catalog, || Ok(Some(manufacturers())), Duration::from_millis(10)));
+        thread::sleep(Duration::from_millis(200));
+
+        assert_eq!(2, catalog.read().unwrap().manufacturers.len());
+    }
+}
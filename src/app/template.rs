@@ -0,0 +1,255 @@
+use std::error::Error;
+use std::fs;
+use handlebars::{handlebars_helper, no_escape, Handlebars};
+use serde_json::{json, Value};
+
+use super::data::{Hardware, Manufacturer, Model, Registry, Revision};
+
+/// # `Builtin` represents a template shipped with odin, picked by its name
+///
+/// `brand` - rendered for each brand, when brands are listed
+/// `model` - rendered for each model, when models are listed
+struct Builtin {
+    name: &'static str,
+    brand: &'static str,
+    model: &'static str
+}
+
+const BUILTINS: [Builtin; 3] = [
+    Builtin {
+        name: "markdown",
+        brand: "{{#if first}}| Brand | Models |\n| --- | --- |\n{{/if}}| {{markdown name}} | {{count}} |\n",
+        model: "{{#if first}}| Brand | Model | Versions | Release | Device Page |\n| --- | --- | --- | --- | --- |\n{{/if}}\
+                {{#each revisions}}| {{markdown ../brand}} | {{markdown ../name}} | {{markdown versions}} | {{markdown release}} | \
+                {{markdown device_page}} |\n{{/each}}"
+    },
+    Builtin {
+        name: "dokuwiki",
+        brand: "{{#if first}}^ Brand ^ Models ^\n{{/if}}| {{dokuwiki name}} | {{count}} |\n",
+        model: "{{#if first}}^ Brand ^ Model ^ Versions ^ Release ^ Device Page ^\n{{/if}}\
+                {{#each revisions}}| {{dokuwiki ../brand}} | {{dokuwiki ../name}} | {{dokuwiki versions}} | {{dokuwiki release}} | \
+                [[{{dokuwiki_link device_page}}]] |\n{{/each}}"
+    },
+    Builtin {
+        name: "line",
+        brand: "{{name}} - {{count}} model(s)\n",
+        model: "{{brand}} {{name}}{{#each revisions}}{{#if @first}}:{{else}};{{/if}} {{#if versions}}{{versions}} {{/if}}{{release}}{{/each}}\n"
+    }
+];
+
+/// Escapes `text` for a Markdown table cell, where `|` ends the cell
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Escapes `text` for a DokuWiki table cell, where `|` & `^` end the cell, by keeping them
+/// from being parsed
+fn escape_dokuwiki(text: &str) -> String {
+    text.replace('|', "%%|%%")
+        .replace('^', "%%^%%")
+}
+
+/// Escapes `url` for a DokuWiki link in a table cell, where `|` would end the link's target
+fn escape_dokuwiki_link(url: &str) -> String {
+    url.replace('|', "%7C")
+        .replace('^', "%5E")
+        .replace(']', "%5D")
+}
+
+handlebars_helper!(markdown: |text: str| escape_markdown(text));
+handlebars_helper!(dokuwiki: |text: str| escape_dokuwiki(text));
+handlebars_helper!(dokuwiki_link: |url: str| escape_dokuwiki_link(url));
+
+/// Returns what templates can look at for `revision`, along with its lifecycle status when `registry` is given
+fn revision_context(revision: &Revision, registry: Option<&Registry>) -> Value {
+    json!({
+        "versions": revision.versions_text(),
+        "hw_versions": revision.versions(),
+        "release": revision.openwrt_version().version(),
+        "release_link": revision.openwrt_version().link(),
+        "device_page": revision.device_page(),
+        "status": registry.and_then(|registry| registry.revision_status(revision)).map(|status| status.to_string()),
+        "hardware": revision.hardware(),
+        "resources": revision.hardware().map(Hardware::resources)
+    })
+}
+
+/// Returns what templates can look at for `model` by `manufacturer`, the release & device page
+/// being the ones of its revision with the latest supported release
+fn model_context(manufacturer: &Manufacturer, model: &Model, registry: Option<&Registry>) -> Value {
+    let latest = model.latest();
+
+    json!({
+        "brand": manufacturer.name(),
+        "name": model.name(),
+        "versions": model.versions_text(),
+        "release": latest.map(|revision| revision.openwrt_version().version().as_str()).unwrap_or_default(),
+        "device_page": latest.map(|revision| revision.device_page().as_str()).unwrap_or_default(),
+        "status": registry.and_then(|registry| registry.model_status(model)).map(|status| status.to_string()),
+        "revisions": model.revisions().iter()
+            .map(|revision| revision_context(revision, registry))
+            .collect::<Vec<Value>>()
+    })
+}
+
+/// # `Template` represents a handlebars template brands or models are rendered through, one at a time
+///
+/// Each brand has its `name`, `count` of models & `models`, each model its `brand`, `name`,
+/// `versions`, `release`, `device_page`, `status` & `revisions`, with their own `versions`,
+/// `hw_versions`, `release`, `release_link`, `device_page`, `status`, `hardware` & `resources`.
+/// `index`, `first` & `last` tell where in the listing an item is, ex: to write a header once.
+///
+/// Values aren't escaped, the `markdown` & `dokuwiki` helpers escape one for a table cell of
+/// either & `dokuwiki_link` for the target of a DokuWiki link. Each rendered item ends with
+/// a line break.
+///
+/// ## Example
+///
+/// {{brand}} {{name}}{{#each revisions}} {{versions}} ({{release}}){{/each}}
+pub struct Template {
+    handlebars: Handlebars<'static>
+}
+
+impl Template {
+    /// Returns the built-in template named `text`, else one of `text`
+    pub fn new(text: &str) -> Result<Self, Box<dyn Error>> {
+        match BUILTINS.iter().find(|builtin| builtin.name == text) {
+            Some(builtin) => Self::with(builtin.brand, builtin.model),
+            None => Self::with(text, text)
+        }
+    }
+
+    /// Loads the template in `file`
+    pub fn load(file: &str) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(shellexpand::tilde(file).trim())
+            .map_err(|err| format!("{}: {}", file, err))?;
+
+        Self::with(&text, &text).map_err(|err| format!("{}: {}", file, err).into())
+    }
+
+    fn with(brand: &str, model: &str) -> Result<Self, Box<dyn Error>> {
+        let mut handlebars = Handlebars::new();
+
+        handlebars.register_escape_fn(no_escape);
+        handlebars.register_helper("markdown", Box::new(markdown));
+        handlebars.register_helper("dokuwiki", Box::new(dokuwiki));
+        handlebars.register_helper("dokuwiki_link", Box::new(dokuwiki_link));
+        handlebars.register_template_string("brand", brand)
+            .and_then(|_| handlebars.register_template_string("model", model))
+            .map_err(|err| match err.pos() {
+                Some((line, column)) => format!("Invalid template at {}:{}: {}", line, column, err.reason()),
+                None => format!("Invalid template: {}", err.reason())
+            })?;
+
+        Ok(Self { handlebars })
+    }
+
+    /// Renders `items` through template `name` one by one, telling each where it is
+    fn render(&self, name: &str, items: Vec<Value>) -> Result<String, Box<dyn Error>> {
+        let count = items.len();
+        let mut rendered = String::new();
+
+        for (i, mut item) in items.into_iter().enumerate() {
+            item["index"] = json!(i + 1);
+            item["first"] = json!(i == 0);
+            item["last"] = json!(i + 1 == count);
+
+            let text = self.handlebars.render(name, &item)
+                .map_err(|err| format!("Couldn't render template: {}", err.reason()))?;
+
+            rendered.push_str(&text);
+
+            if !text.ends_with('\n') {
+                rendered.push('\n');
+            }
+        }
+
+        Ok(rendered)
+    }
+
+    /// Renders each of `manufacturers`
+    pub fn render_brands(&self, manufacturers: &[&Manufacturer]) -> Result<String, Box<dyn Error>> {
+        let items = manufacturers.iter()
+            .map(|manufacturer| json!({
+                "name": manufacturer.name(),
+                "count": manufacturer.count(),
+                "models": manufacturer.models().iter()
+                    .map(|model| model_context(manufacturer, model, None))
+                    .collect::<Vec<Value>>()
+            }))
+            .collect();
+
+        self.render("brand", items)
+    }
+
+    /// Renders each model of `manufacturers`, along with lifecycle status of their release when
+    /// `registry` is given
+    pub fn render_models(&self, manufacturers: &[&Manufacturer], registry: Option<&Registry>) -> Result<String, Box<dyn Error>> {
+        let items = manufacturers.iter()
+            .flat_map(|manufacturer| manufacturer.models().iter()
+                .map(move |model| model_context(manufacturer, model, registry)))
+            .collect();
+
+        self.render("model", items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::{manufacturers, model, revision_with_page};
+
+    #[test]
+    fn renders_builtin_templates() {
+        let manufacturers = manufacturers();
+        let manufacturers = manufacturers.iter().collect::<Vec<&Manufacturer>>();
+        let netgear = &manufacturers[..1];
+
+        assert_eq!("\
+            | Brand | Model | Versions | Release | Device Page |\n\
+            | --- | --- | --- | --- | --- |\n\
+            | Netgear | R7800 |  | 19.07.3 | https://openwrt.org/toh/netgear/r7800 |\n\
+            | Netgear | WNDR3700/WNDR3800 | v1 | 19.07.3 | https://openwrt.org/toh/netgear/wndr3700 |\n",
+            Template::new("markdown").unwrap().render_models(netgear, None).unwrap());
+
+        assert_eq!("Netgear R7800: 19.07.3\nNetgear WNDR3700/WNDR3800: v1 19.07.3\n",
+            Template::new("line").unwrap().render_models(netgear, None).unwrap());
+
+        assert_eq!("Netgear - 2 model(s)\nTP-Link - 4 model(s)\n",
+            Template::new("line").unwrap().render_brands(&manufacturers).unwrap());
+    }
+
+    #[test]
+    fn escapes_table_cells_of_builtin_templates() {
+        let manufacturers = [Manufacturer::new("A|B^C".to_string(), vec![
+            model("X|Y", vec![revision_with_page(&["v1^2"], "19.07.3", "https://example.com/a|b^c]")])
+        ])];
+        let manufacturers = manufacturers.iter().collect::<Vec<&Manufacturer>>();
+
+        assert_eq!("\
+            | Brand | Model | Versions | Release | Device Page |\n\
+            | --- | --- | --- | --- | --- |\n\
+            | A\\|B^C | X\\|Y | v1^2 | 19.07.3 | https://example.com/a\\|b^c] |\n",
+            Template::new("markdown").unwrap().render_models(&manufacturers, None).unwrap());
+
+        assert_eq!("\
+            ^ Brand ^ Model ^ Versions ^ Release ^ Device Page ^\n\
+            | A%%|%%B%%^%%C | X%%|%%Y | v1%%^%%2 | 19.07.3 | [[https://example.com/a%7Cb%5Ec%5D]] |\n",
+            Template::new("dokuwiki").unwrap().render_models(&manufacturers, None).unwrap());
+
+        assert_eq!("^ Brand ^ Models ^\n| A%%|%%B%%^%%C | 1 |\n",
+            Template::new("dokuwiki").unwrap().render_brands(&manufacturers).unwrap());
+    }
+
+    #[test]
+    fn renders_given_templates() {
+        let manufacturers = manufacturers();
+        let manufacturers = manufacturers.iter().collect::<Vec<&Manufacturer>>();
+        let template = Template::new("{{index}}. {{name}} <{{#each models}}{{name}}{{#unless @last}}, {{/unless}}{{/each}}>{{#if last}}.{{/if}}").unwrap();
+
+        assert_eq!("1. Netgear <R7800, WNDR3700/WNDR3800>\n2. TP-Link <Archer C7, Archer C7 AC1750, TL-WR703N, TL-WR841N>.\n",
+            template.render_brands(&manufacturers).unwrap());
+
+        assert!(Template::new("{{#each revisions}").err().unwrap().to_string().starts_with("Invalid template at 1:18: "));
+    }
+}
@@ -0,0 +1,525 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+use super::data::{self, Manufacturer, Model, Revision};
+
+/// Columns of the model table, in display order
+const COLUMNS: [Column; 4] = [Column::Model, Column::Version, Column::Release, Column::DevicePage];
+
+/// Row of the model table, a hardware revision along with its model
+pub type Entry<'m> = (&'m Model, &'m Revision);
+
+/// # `Column` represents a column of the model table, which rows can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    Model,
+    Version,
+    Release,
+    DevicePage
+}
+
+impl Column {
+    fn title(self) -> &'static str {
+        match self {
+            Column::Model => "Model",
+            Column::Version => "Version",
+            Column::Release => "OpenWrt Version",
+            Column::DevicePage => "Device Page"
+        }
+    }
+
+    fn compare(self, (this_model, this): Entry, (that_model, that): Entry) -> Ordering {
+        match self {
+            Column::Model => this_model.name().to_lowercase().cmp(&that_model.name().to_lowercase()),
+            Column::Version => this.versions_text().cmp(&that.versions_text()),
+            Column::Release => this.openwrt_version().release().cmp(&that.openwrt_version().release()),
+            Column::DevicePage => this.device_page().cmp(that.device_page())
+        }
+    }
+}
+
+/// Pane that has keyboard focus
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    Brands,
+    Models
+}
+
+/// # `Browser` represents the state of the terminal UI over a list of brands
+///
+/// `manufacturers` - all brands, sorted by name
+/// `filter` - text brands & models are filtered by
+/// `sort` - column rows of the model table are sorted by, `None` keeps the order of the page
+/// `ascending` - whether rows are sorted in ascending order
+pub struct Browser<'m> {
+    manufacturers: Vec<&'m Manufacturer>,
+    filter: String,
+    filtering: bool,
+    sort: Option<Column>,
+    ascending: bool,
+    focus: Focus,
+    brands: ListState,
+    models: TableState,
+    message: String
+}
+
+impl<'m> Browser<'m> {
+    pub fn new(manufacturers: &'m [Manufacturer]) -> Self {
+        let mut manufacturers = manufacturers.iter()
+            .collect::<Vec<&Manufacturer>>();
+
+        manufacturers.sort_by_key(|manufacturer| manufacturer.name().to_lowercase());
+
+        Self {
+            manufacturers,
+            filter: String::new(),
+            filtering: false,
+            sort: None,
+            ascending: true,
+            focus: Focus::Brands,
+            brands: ListState::default().with_selected(Some(0)),
+            models: TableState::default().with_selected(Some(0)),
+            message: String::new()
+        }
+    }
+
+    /// Returns whether `manufacturer` itself matches the filter
+    fn is_brand_match(&self, manufacturer: &Manufacturer) -> bool {
+        data::normalize(manufacturer.name()).contains(&data::normalize(&self.filter))
+    }
+
+    /// Returns whether `model` matches the filter, by its name or the versions of its revisions
+    fn is_model_match(&self, model: &Model) -> bool {
+        let filter = data::normalize(&self.filter);
+
+        data::normalize(model.name()).contains(&filter) ||
+            data::normalize(&model.versions_text()).contains(&filter)
+    }
+
+    /// Returns brands matching the filter, by their name or by any of their models
+    pub fn visible_brands(&self) -> Vec<&'m Manufacturer> {
+        self.manufacturers.iter()
+            .filter(|manufacturer| self.is_brand_match(manufacturer) ||
+                manufacturer.models().iter().any(|model| self.is_model_match(model)))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the selected brand, if any
+    pub fn selected_brand(&self) -> Option<&'m Manufacturer> {
+        self.visible_brands()
+            .get(self.brands.selected().unwrap_or_default())
+            .copied()
+    }
+
+    /// Returns hardware revisions of the selected brand's models matching the filter, in sort order
+    ///
+    /// All models are shown when the brand itself matches the filter
+    pub fn visible_models(&self) -> Vec<Entry<'m>> {
+        let manufacturer = match self.selected_brand() {
+            Some(manufacturer) => manufacturer,
+            None => return Vec::new()
+        };
+        let is_brand_match = self.is_brand_match(manufacturer);
+        let mut models = manufacturer.models()
+            .iter()
+            .filter(|model| is_brand_match || self.is_model_match(model))
+            .flat_map(|model| model.revisions()
+                .iter()
+                .map(move |revision| (model, revision)))
+            .collect::<Vec<Entry>>();
+
+        if let Some(column) = self.sort {
+            models.sort_by(|this, that| column.compare(*this, *that));
+
+            if !self.ascending {
+                models.reverse();
+            }
+        }
+
+        models
+    }
+
+    /// Returns the selected hardware revision along with its model, if any
+    pub fn selected_model(&self) -> Option<Entry<'m>> {
+        self.visible_models()
+            .get(self.models.selected().unwrap_or_default())
+            .copied()
+    }
+
+    /// Sorts rows of the model table by `column`, toggling the order when already sorted by it
+    pub fn sort_by(&mut self, column: Column) {
+        if self.sort == Some(column) {
+            self.ascending = !self.ascending;
+        }
+        else {
+            self.sort = Some(column);
+            self.ascending = true;
+        }
+
+        self.models.select(Some(0));
+    }
+
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        self.brands.select(Some(0));
+        self.models.select(Some(0));
+    }
+
+    /// Moves selection of the focused pane by `offset`, staying within its items
+    fn move_selection(&mut self, offset: isize) {
+        let (count, selected) = match self.focus {
+            Focus::Brands => (self.visible_brands().len(), self.brands.selected()),
+            Focus::Models => (self.visible_models().len(), self.models.selected())
+        };
+
+        if count == 0 {
+            return;
+        }
+
+        let selected = (selected.unwrap_or_default() as isize + offset)
+            .clamp(0, count as isize - 1) as usize;
+
+        match self.focus {
+            Focus::Brands => {
+                self.brands.select(Some(selected));
+                self.models.select(Some(0));
+            },
+            Focus::Models => self.models.select(Some(selected))
+        }
+    }
+
+    /// Handles `key`, returns `false` when the browser should quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.message.clear();
+
+        if self.filtering {
+            match key.code {
+                KeyCode::Enter => self.filtering = false,
+                KeyCode::Esc => {
+                    self.filtering = false;
+                    self.set_filter("");
+                },
+                KeyCode::Backspace => {
+                    let mut filter = self.filter.clone();
+
+                    filter.pop();
+                    self.set_filter(&filter);
+                },
+                KeyCode::Char(c) => {
+                    let filter = format!("{}{}", self.filter, c);
+
+                    self.set_filter(&filter);
+                },
+                _ => ()
+            }
+
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('/') => self.filtering = true,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Left | KeyCode::Right => {
+                self.focus = match self.focus {
+                    Focus::Brands => Focus::Models,
+                    Focus::Models => Focus::Brands
+                };
+            },
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN / 2),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX / 2),
+            KeyCode::Char(c @ '1'..='4') => {
+                let index = c.to_digit(10).unwrap_or(1) as usize - 1;
+
+                self.sort_by(COLUMNS[index]);
+            },
+            KeyCode::Char('y') => self.copy_device_page(),
+            KeyCode::Char('o') => self.open_device_page(),
+            _ => ()
+        }
+
+        true
+    }
+
+    /// Copies device page of the selected hardware revision to the clipboard, with an OSC 52 escape sequence
+    fn copy_device_page(&mut self) {
+        let device_page = match self.selected_model().map(|(_, revision)| revision.device_page()) {
+            Some(device_page) if !device_page.is_empty() => device_page,
+            _ => {
+                self.message = "No device page to copy!".to_string();
+
+                return;
+            }
+        };
+        let mut stdout = io::stdout();
+
+        let _ = write!(stdout, "\x1b]52;c;{}\x07", base64::encode(device_page));
+        let _ = stdout.flush();
+
+        self.message = format!("Copied {}", device_page);
+    }
+
+    /// Opens device page of the selected hardware revision in the default browser
+    fn open_device_page(&mut self) {
+        let device_page = match self.selected_model().map(|(_, revision)| revision.device_page()) {
+            Some(device_page) if !device_page.is_empty() => device_page,
+            _ => {
+                self.message = "No device page to open!".to_string();
+
+                return;
+            }
+        };
+        let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+        let result = Command::new(opener)
+            .arg(device_page)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        self.message = match result {
+            Ok(_) => format!("Opened {}", device_page),
+            Err(err) => format!("Error running {}: {}", opener, err)
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, detail, status] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(8),
+            Constraint::Length(1)
+        ]).areas(frame.area());
+        let [brands, models] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(75)
+        ]).areas(main);
+
+        self.draw_brands(frame, brands);
+        self.draw_models(frame, models);
+        self.draw_detail(frame, detail);
+        self.draw_status(frame, status);
+    }
+
+    fn block(&self, title: String, focus: Focus) -> Block<'static> {
+        let style = if self.focus == focus {
+            Style::default().fg(Color::Cyan)
+        }
+        else {
+            Style::default()
+        };
+
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style)
+            .title(title)
+    }
+
+    fn draw_brands(&mut self, frame: &mut Frame, area: Rect) {
+        let brands = self.visible_brands();
+        let items = brands.iter()
+            .map(|manufacturer| ListItem::new(format!("{} ({})", manufacturer.name(), manufacturer.count())))
+            .collect::<Vec<ListItem>>();
+        let list = List::new(items)
+            .block(self.block(format!("Brands ({})", brands.len()), Focus::Brands))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(list, area, &mut self.brands);
+    }
+
+    fn draw_models(&mut self, frame: &mut Frame, area: Rect) {
+        let models = self.visible_models();
+        let header = COLUMNS.iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let order = match self.sort {
+                    Some(sort) if sort == *column => if self.ascending { " ▲" } else { " ▼" },
+                    _ => ""
+                };
+
+                Cell::from(format!("{}. {}{}", i + 1, column.title(), order))
+            })
+            .collect::<Row>()
+            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        let rows = models.iter()
+            .map(|(model, revision)| Row::new(vec![
+                model.name().to_string(),
+                revision.versions_text(),
+                revision.openwrt_version().version().to_string(),
+                revision.device_page().to_string()
+            ]))
+            .collect::<Vec<Row>>();
+        let title = match self.selected_brand() {
+            Some(manufacturer) => format!("{} ({} revision(s))", manufacturer.name(), models.len()),
+            None => "Models".to_string()
+        };
+        let table = Table::new(rows, [
+            Constraint::Percentage(25),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(45)
+        ])
+            .header(header)
+            .block(self.block(title, Focus::Models))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.models);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        let not_available = |val: &str| if val.is_empty() { "N/A".to_string() } else { val.to_string() };
+        let lines = match (self.selected_brand(), self.selected_model()) {
+            (Some(manufacturer), Some((model, revision))) => vec![
+                Line::from(vec![label("Brand: "), Span::raw(manufacturer.name().to_string())]),
+                Line::from(vec![label("Model: "), Span::raw(model.name().to_string())]),
+                Line::from(vec![label("Revisions: "), Span::raw(describe_revisions(model))]),
+                Line::from(vec![label("Version: "), Span::raw(not_available(&revision.versions_text()))]),
+                Line::from(vec![label("OpenWrt Version: "), Span::raw(not_available(revision.openwrt_version().version()))]),
+                Line::from(vec![label("Device Page: "), Span::raw(not_available(revision.device_page()))])
+            ],
+            _ => vec![Line::from("Found 0 model(s)!")]
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Details"));
+
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let text = if self.filtering {
+            format!("/{}", self.filter)
+        }
+        else if !self.message.is_empty() {
+            self.message.clone()
+        }
+        else if !self.filter.is_empty() {
+            format!("Filter: {} | / edit, Esc quit", self.filter)
+        }
+        else {
+            "q quit | Tab switch pane | / filter | 1-4 sort | y copy device page | o open device page".to_string()
+        };
+
+        frame.render_widget(Paragraph::new(text).style(Style::default().add_modifier(Modifier::DIM)), area);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Box<dyn Error>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Describes all hardware revisions of `model` on one line, ex: "v1 (17.01.7), v2, v3 (19.07.3)"
+fn describe_revisions(model: &Model) -> String {
+    model.revisions()
+        .iter()
+        .map(|revision| {
+            let versions = if revision.versions_text().is_empty() { "N/A".to_string() } else { revision.versions_text() };
+            let release = revision.openwrt_version().version();
+
+            format!("{} ({})", versions, if release.is_empty() { "N/A" } else { release })
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Browses `manufacturers` in a full screen terminal UI, until the user quits
+pub fn browse(manufacturers: &[Manufacturer]) -> Result<(), Box<dyn Error>> {
+    let mut browser = Browser::new(manufacturers);
+    let mut terminal = ratatui::init();
+    let result = browser.run(&mut terminal);
+
+    ratatui::restore();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::manufacturers;
+
+    fn names(models: Vec<Entry<'_>>) -> Vec<&str> {
+        models.iter()
+            .map(|(model, _)| model.name().as_str())
+            .collect()
+    }
+
+    #[test]
+    fn filters_brands_and_models() {
+        let manufacturers = manufacturers();
+        let mut browser = Browser::new(&manufacturers);
+
+        assert_eq!(2, browser.visible_brands().len());
+        assert_eq!("Netgear", browser.selected_brand().unwrap().name());
+        assert_eq!(vec!["R7800", "WNDR3700/WNDR3800"], names(browser.visible_models()));
+
+        browser.set_filter("archer");
+        assert_eq!(1, browser.visible_brands().len());
+        assert_eq!(vec!["Archer C7", "Archer C7", "Archer C7", "Archer C7 AC1750"], names(browser.visible_models()));
+
+        browser.set_filter("tp-");
+        assert_eq!(6, browser.visible_models().len());
+
+        browser.set_filter("unknown");
+        assert!(browser.selected_brand().is_none());
+        assert!(browser.visible_models().is_empty());
+    }
+
+    #[test]
+    fn sorts_models_by_column() {
+        let manufacturers = manufacturers();
+        let mut browser = Browser::new(&manufacturers);
+
+        browser.set_filter("tl-");
+        browser.sort_by(Column::Release);
+        assert_eq!(vec!["TL-WR703N", "TL-WR841N"], names(browser.visible_models()));
+
+        browser.sort_by(Column::Release);
+        assert_eq!(vec!["TL-WR841N", "TL-WR703N"], names(browser.visible_models()));
+
+        browser.set_filter("archer");
+        browser.sort_by(Column::Model);
+        assert_eq!(vec!["Archer C7", "Archer C7", "Archer C7", "Archer C7 AC1750"], names(browser.visible_models()));
+
+        browser.sort_by(Column::Release);
+        assert_eq!(vec!["Archer C7", "Archer C7 AC1750", "Archer C7", "Archer C7"], names(browser.visible_models()));
+    }
+
+    #[test]
+    fn moves_selection_within_panes() {
+        let manufacturers = manufacturers();
+        let mut browser = Browser::new(&manufacturers);
+
+        browser.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!("TP-Link", browser.selected_brand().unwrap().name());
+
+        browser.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!("TP-Link", browser.selected_brand().unwrap().name());
+
+        browser.handle_key(KeyEvent::from(KeyCode::Tab));
+        browser.handle_key(KeyEvent::from(KeyCode::Char('2')));
+        browser.handle_key(KeyEvent::from(KeyCode::End));
+        assert_eq!("TL-WR841N", browser.selected_model().unwrap().0.name());
+
+        assert!(!browser.handle_key(KeyEvent::from(KeyCode::Char('q'))));
+    }
+}
@@ -0,0 +1,179 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+
+use super::data::{self, Manufacturer};
+
+/// # `FollowedDevice` represents a device on the watchlist
+///
+/// `brand` - brand of the device
+/// `model` - model of the device
+/// `version` - hardware version of the device, if any
+/// `release` - supported OpenWrt release, as of the last time it was checked
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FollowedDevice {
+    brand: String,
+    model: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    release: Option<String>
+}
+
+impl FollowedDevice {
+    pub fn new(brand: &str, model: &str, version: Option<&str>) -> Self {
+        Self {
+            brand: brand.to_string(),
+            model: model.to_string(),
+            version: version.map(|version| version.to_string()),
+            release: None
+        }
+    }
+
+    /// Returns whether this is the same device as `other`, see `data::normalize` & `data::normalize_version`
+    fn is_same(&self, other: &FollowedDevice) -> bool {
+        let version = |device: &FollowedDevice| device.version.as_deref()
+            .map(data::normalize_version)
+            .unwrap_or_default();
+
+        data::normalize(&self.brand) == data::normalize(&other.brand) &&
+            data::normalize(&self.model) == data::normalize(&other.model) &&
+            version(self) == version(other)
+    }
+
+    /// Returns the supported release of this device in `manufacturers`,
+    /// `None` if it isn't there or has no supported release
+    fn current_release(&self, manufacturers: &[Manufacturer]) -> Option<String> {
+        data::find_manufacturer(manufacturers, &self.brand)
+            .and_then(|manufacturer| manufacturer.find_revision(&self.model, self.version.as_deref()))
+            .map(|(_, revision)| revision.openwrt_version().version().to_string())
+            .filter(|release| !release.is_empty())
+    }
+
+    /// Returns a one line description of this device, ex: TP-Link Archer C7 v2
+    pub fn describe(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{} {} {}", self.brand, self.model, version),
+            None => format!("{} {}", self.brand, self.model)
+        }
+    }
+}
+
+/// # `Change` represents a followed device, along with how its release changed
+#[derive(Debug)]
+pub struct Change {
+    pub device: FollowedDevice,
+    pub previous: Option<String>,
+    pub current: Option<String>
+}
+
+impl Change {
+    pub fn is_changed(&self) -> bool {
+        self.previous != self.current
+    }
+}
+
+/// # `Watchlist` represents devices followed by the user, kept in odin home
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Watchlist {
+    devices: Vec<FollowedDevice>
+}
+
+impl Watchlist {
+    /// Loads the watchlist from `file`, an empty one if it doesn't exist yet
+    pub fn load(file: &str) -> Result<Self, Box<dyn Error>> {
+        let file = shellexpand::tilde(file);
+        let path = Path::new(file.trim());
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Saves the watchlist to `file`
+    pub fn save(&self, file: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(shellexpand::tilde(file).trim(), serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn devices(&self) -> &Vec<FollowedDevice> {
+        &self.devices
+    }
+
+    /// Follows `device`, noting its current release in `manufacturers`
+    /// Returns `false` if it was already followed
+    pub fn follow(&mut self, mut device: FollowedDevice, manufacturers: &[Manufacturer]) -> bool {
+        if self.devices.iter().any(|followed| followed.is_same(&device)) {
+            return false;
+        }
+
+        device.release = device.current_release(manufacturers);
+        self.devices.push(device);
+
+        true
+    }
+
+    /// Stops following `device`, returns `false` if it wasn't followed
+    pub fn unfollow(&mut self, device: &FollowedDevice) -> bool {
+        let count = self.devices.len();
+
+        self.devices.retain(|followed| !followed.is_same(device));
+
+        self.devices.len() != count
+    }
+
+    /// Compares followed devices against `manufacturers`, noting their current releases as seen
+    pub fn update(&mut self, manufacturers: &[Manufacturer]) -> Vec<Change> {
+        self.devices.iter_mut()
+            .map(|device| {
+                let previous = device.release.clone();
+                let current = device.current_release(manufacturers);
+
+                device.release = current.clone();
+
+                Change { device: device.clone(), previous, current }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::data::fixtures::{manufacturers, manufacturers_on};
+
+    #[test]
+    fn follows_devices_once() {
+        let mut watchlist = Watchlist::default();
+
+        assert!(watchlist.follow(FollowedDevice::new("TP-Link", "Archer C7", Some("v2")), &manufacturers()));
+        assert!(!watchlist.follow(FollowedDevice::new("tp-link", "archer c7", Some("V2")), &manufacturers()));
+        assert_eq!(1, watchlist.devices().len());
+        assert_eq!(Some("19.07.3".to_string()), watchlist.devices()[0].release);
+
+        assert!(watchlist.unfollow(&FollowedDevice::new("TP-LINK", "Archer C7", Some("v2"))));
+        assert!(!watchlist.unfollow(&FollowedDevice::new("TP-LINK", "Archer C7", Some("v2"))));
+        assert!(watchlist.devices().is_empty());
+    }
+
+    #[test]
+    fn detects_release_changes() {
+        let mut watchlist = Watchlist::default();
+
+        watchlist.follow(FollowedDevice::new("TP-Link", "Archer C7", Some("v2")), &manufacturers_on("18.06.8"));
+        watchlist.follow(FollowedDevice::new("Netgear", "R7800", None), &manufacturers_on("18.06.8"));
+
+        let changes = watchlist.update(&manufacturers());
+        assert_eq!(2, changes.len());
+        assert!(changes[0].is_changed(), "Expected release change for {}", changes[0].device.describe());
+        assert_eq!(Some("19.07.3".to_string()), changes[0].current);
+        assert!(!changes[1].is_changed(), "Expected no change for {}", changes[1].device.describe());
+
+        let changes = watchlist.update(&manufacturers());
+        assert!(changes.iter().all(|change| !change.is_changed()));
+    }
+}
@@ -1,30 +1,130 @@
-use clap::{self, Arg, App, SubCommand, ArgMatches};
+use clap::{self, Arg, App, AppSettings, SubCommand, ArgMatches};
+use odin::app::Format;
 
 /// # Setup CLI interface for ODIN and parses arguments
 /// 
+/// Diagnostics go to stderr, leveled by `-v`/`-vv`/`-q` or `ODIN_LOG`, stdout carries only
+/// the requested data
+///
 /// ## Usage
 /// 
 /// 1. List all brand names
-///     `odin list`
+///    `odin list`
 /// 2. List all models for given brand
-///     `odin list -m -b tp-link`
+///    `odin list -m -b tp-link`
 /// 3. List all models for all available brands
-///     `odin list`
-/// 4. Global help
+///    `odin list`
+/// 4. Write all brands as a versioned JSON catalog, load it back & print its JSON Schema
+///    `odin list --format json > catalog.json`
+///    `odin -f catalog.json list -m -b tp-link`
+///    `odin schema`
+/// 5. List release branches & list models annotated by their release status, from a saved releases page
+///    `odin --releases releases.html releases`
+///    `odin --releases releases.html list -m -b tp-link --status`
+/// 6. List models with at least 16 MB flash & 128 MB RAM, with technical data from the ToH dump
+///    `odin --hardware toh_dump_tab_separated.gz list -m -a --min-flash 16 --min-ram 128`
+/// 7. Compare models side by side, with technical data from the ToH dump
+///    `odin --hardware toh.tsv compare "tp-link/archer c7@v2" netgear/r7800`
+/// 8. Recommend models supported on the latest release, with 128 MB RAM, 5 GHz Wi-Fi & 4 LAN ports
+///    `odin --hardware toh.tsv recommend --release latest --min-ram 128 --5ghz --lan-ports 4`
+/// 9. List TP-Link models supported on 19.07 or newer & count models per release
+///    `odin query 'brand ~ "tp-link" and release >= 19.07 and hw_version in ["v4", "v5"]'`
+///    `odin query --group-by release --count`
+/// 10. List all models as a Markdown table, or through a template of your own
+///     `odin list -m -a --template markdown`
+///     `odin list -m -b tp-link --template-file wiki.hbs`
+/// 11. Report data quality problems of the devices page
+///     `odin -f devices.html lint`
+/// 12. Check device pages for dead links, on a local mirror
+///     `odin check-links --mirror http://localhost:8000`
+/// 13. Report fleet support status
+///     `odin fleet report inventory.csv --format json`
+/// 14. Export the catalog into a SQLite database
+///     `odin export sqlite odin.db`
+/// 15. Follow a model & check for release changes
+///     `odin follow tp-link "archer c7" v2`
+///     `odin status --exec 'notify-send odin "$ODIN_CHANGES"'`
+/// 16. Identify a router from its board details
+///     `ubus call system board | odin identify --board -`
+/// 17. List firmware images of a model, from the firmware selector's profiles.json
+///     `odin images tp-link "archer c7" v2 --profiles ~/Downloads/profiles.json`
+/// 18. Serve the catalog as JSON on localhost
+///     `odin serve --port 8080`
+/// 19. Browse the catalog in a full screen terminal UI
+///     `odin tui`
+/// 20. Generate shell completions
+///     `odin completions bash > /etc/bash_completion.d/odin`
+/// 21. Global help
 ///     `odin --help`
-/// 5. Subcommand help
+/// 22. Subcommand help
 ///     `odin list --help`
 pub fn parse_args() -> ArgMatches<'static> {
-    let matches = App::new("ODIN - OpenWrt Device Information")
+    build()
+        .subcommand(
+            // kept out of `build`, clap's completion scripts can't deal with `__` in names
+            SubCommand::with_name("__complete")
+                .about("Lists brand or model names for shell completions, without touching the network")
+                .setting(AppSettings::Hidden)
+                .arg(
+                    Arg::with_name("kind")
+                        .required(true)
+                        .possible_values(&["brands", "models"])
+                )
+                .arg(
+                    Arg::with_name("values")
+                        .help("Brand to list models for, followed by the prefix to complete.")
+                        .multiple(true)
+                )
+        )
+        .get_matches()
+}
+
+/// Builds the CLI interface, shared by argument parsing & shell completions
+pub fn build() -> App<'static, 'static> {
+    App::new("ODIN - OpenWrt Device Information")
         .version("1.0.1")
         .author("Meenakshi Sundaram V <vms20591@riseup.net>")
         .about("CLI for OpenWrt's supported devices page")
         .arg(
             Arg::with_name("file")
                 .short("f")
-                .help("Path to OpenWrt supported device html page, since loading the page via http is slower.")
+                .help("Path to OpenWrt supported device html page, since loading the page via http is slower.\nA JSON catalog, as written by `list --format json`, works too.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("releases")
+                .long("releases")
+                .help("Path to OpenWrt releases html page or a JSON list of releases, used for release status.\nDefaults to ~/.config/odin/releases.html, else the page is loaded via http.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("hardware")
+                .long("hardware")
+                .help("Path to OpenWrt's ToH dump (tab separated, gzipped or not), a saved device page or a directory of them, for flash, RAM & other technical data.\nDefaults to ~/.config/odin/toh.tsv, if present.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("max-body-size")
+                .long("max-body-size")
+                .help("Largest devices page accepted from the web, in MB, larger ones are rejected.\nDefaults to 64.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .help("Shows debug diagnostics on stderr, -vv shows everything.\nODIN_LOG filters them too, ex: ODIN_LOG=warn,loader=trace")
+                .multiple(true)
+                .global(true)
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .short("q")
+                .long("quiet")
+                .help("Shows only errors on stderr, stdout carries the requested data either way.")
+                .conflicts_with("verbose")
+                .global(true)
+        )
         .subcommand(
             SubCommand::with_name("list")
                 .about("Lists all available router brands")
@@ -47,12 +147,331 @@ pub fn parse_args() -> ArgMatches<'static> {
                         .long("all")
                         .help("Lists all available model detals for all router brands.\nOverrides -n/--name option.\nEffective only with -m/--models option.")
                 )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("Output format.\njson writes the listed brands as a catalog, which -f loads back.")
+                        .takes_value(true)
+                        .possible_values(&["table", "json"])
+                        .default_value("table")
+                )
+                .arg(
+                    Arg::with_name("status")
+                        .short("s")
+                        .long("status")
+                        .help("Annotates models as on current release, release candidate only, old stable only or EOL & colors them by status.\nEffective only with -m/--models option, see --releases.")
+                )
+                .arg(
+                    Arg::with_name("min-flash")
+                        .long("min-flash")
+                        .help("Lists only hardware revisions with at least this much flash, in MB.\nEffective only with -m/--models option, see --hardware.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("min-ram")
+                        .long("min-ram")
+                        .help("Lists only hardware revisions with at least this much RAM, in MB.\nEffective only with -m/--models option, see --hardware.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("template")
+                        .long("template")
+                        .help("Renders each brand, or each model with -m/--models, through a handlebars template, ex: '{{brand}} {{name}}{{#each revisions}} {{versions}}{{/each}}'.\n\
+                               A built-in one is picked by name: markdown, dokuwiki or line. Overrides --format.")
+                        .takes_value(true)
+                        .conflicts_with("template-file")
+                )
+                .arg(
+                    Arg::with_name("template-file")
+                        .long("template-file")
+                        .help("Path to a handlebars template to render each brand, or each model with -m/--models, through.\nOverrides --format.")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("releases")
+                .about("Lists OpenWrt release branches with their latest point release, date & status")
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("Output format")
+                        .takes_value(true)
+                        .possible_values(&["table", "json"])
+                        .default_value("table")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("schema")
+                .about("Prints the JSON Schema of the catalog written by list --format json")
+        )
+        .subcommand(
+            SubCommand::with_name("lint")
+                .about("Reports data quality problems of the devices page, fails on structural ones")
+        )
+        .subcommand(
+            SubCommand::with_name("check-links")
+                .about("Checks device pages of all models & reports dead ones")
+                .arg(
+                    Arg::with_name("mirror")
+                        .long("mirror")
+                        .help("Base URL of a mirror or a local stand-in server to check device pages on, ex: http://localhost:8000.\nDevice pages keep their path & query.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .help("Number of device pages checked at a time.")
+                        .takes_value(true)
+                        .default_value("4")
+                )
+                .arg(
+                    Arg::with_name("timeout")
+                        .long("timeout")
+                        .help("Seconds to wait for a device page.")
+                        .takes_value(true)
+                        .default_value("10")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("fleet")
+                .about("Works with an inventory of routers")
+                .subcommand(
+                    SubCommand::with_name("report")
+                        .about("Reports support status of routers in an inventory csv")
+                        .arg(
+                            Arg::with_name("inventory")
+                                .help("Path to inventory csv, with brand, model, version & release columns.")
+                                .required(true)
+                        )
+                        .arg(
+                            Arg::with_name("format")
+                                .long("format")
+                                .help("Output format.")
+                                .takes_value(true)
+                                .possible_values(&Format::NAMES)
+                                .default_value("table")
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("compare")
+                .about("Compares models side by side, highlighting fields that differ")
+                .arg(
+                    Arg::with_name("models")
+                        .help("Models to compare, as brand/model, or brand/model@version for a hardware version, ex: tp-link/archer c7@v2.")
+                        .required(true)
+                        .multiple(true)
+                        .min_values(2)
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("Output format.")
+                        .takes_value(true)
+                        .possible_values(&["table", "json"])
+                        .default_value("table")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("query")
+                .about("Lists hardware revisions matching an expression, grouped or counted")
+                .arg(
+                    Arg::with_name("expression")
+                        .help("Expression to match, ex: 'brand ~ \"tp-link\" and release >= 19.07 and hw_version in [\"v4\", \"v5\"]'. \
+                               Fields are brand, model, hw_version, release, device_page, soc, flash_mb, ram_mb, wlan_24ghz, \
+                               wlan_50ghz & ethernet_ports, compared with =, !=, <, <=, >, >=, ~ (regex), !~ or in [...] & \
+                               combined with and, or, not & parentheses. All hardware revisions match without one.")
+                )
+                .arg(
+                    Arg::with_name("group-by")
+                        .long("group-by")
+                        .help("Groups matches by their value of a field, ex: release.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("count")
+                        .long("count")
+                        .help("Prints only the number of matches, per group with --group-by.")
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("Output format.")
+                        .takes_value(true)
+                        .possible_values(&["table", "json"])
+                        .default_value("table")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("recommend")
+                .about("Recommends models meeting requirements, newer releases & more headroom first")
+                .arg(
+                    Arg::with_name("requirements")
+                        .long("requirements")
+                        .help("Path to a JSON file of requirements, flags take precedence over it.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("release")
+                        .long("release")
+                        .help("Oldest acceptable supported release, ex: 19.07, or latest for the newest release branch.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("min-flash")
+                        .long("min-flash")
+                        .help("Minimum flash size, in MB.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("min-ram")
+                        .long("min-ram")
+                        .help("Minimum RAM size, in MB.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("wifi-5ghz")
+                        .long("5ghz")
+                        .help("Requires 5 GHz Wi-Fi.")
+                )
+                .arg(
+                    Arg::with_name("lan-ports")
+                        .long("lan-ports")
+                        .help("Minimum number of LAN ports, one ethernet port is taken as WAN.")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .short("n")
+                        .long("limit")
+                        .help("Maximum number of recommendations.")
+                        .takes_value(true)
+                        .default_value("10")
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("Output format.")
+                        .takes_value(true)
+                        .possible_values(&["table", "json"])
+                        .default_value("table")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Exports the catalog for analysis")
+                .subcommand(
+                    SubCommand::with_name("sqlite")
+                        .about("Exports the catalog into a SQLite database, keeping history of previous exports")
+                        .arg(
+                            Arg::with_name("path")
+                                .help("Path to the SQLite database, created if missing.")
+                                .required(true)
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("follow")
+                .about("Adds a model to the watchlist")
+                .args(&device_args())
+        )
+        .subcommand(
+            SubCommand::with_name("unfollow")
+                .about("Removes a model from the watchlist")
+                .args(&device_args())
+        )
+        .subcommand(
+            SubCommand::with_name("status")
+                .about("Lists models on the watchlist, highlighting ones whose supported release changed")
+                .arg(
+                    Arg::with_name("exec")
+                        .long("exec")
+                        .help("Command to run when a supported release changed.\nChanges are passed in ODIN_CHANGES environment variable, one per line.")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("identify")
+                .about("Identifies a router from `ubus call system board` output & reports its upgrade path")
+                .arg(
+                    Arg::with_name("board")
+                        .long("board")
+                        .help("Path to a file with `ubus call system board` output, or - to read it from stdin.")
+                        .takes_value(true)
+                        .required(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("images")
+                .about("Lists firmware images of a model for its supported release")
+                .args(&device_args())
+                .arg(
+                    Arg::with_name("profiles")
+                        .long("profiles")
+                        .help("Path to a profiles.json from OpenWrt's downloads server, or a directory of them.\nDefaults to ~/.config/odin/profiles.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("Output format.")
+                        .takes_value(true)
+                        .possible_values(&Format::NAMES)
+                        .default_value("table")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Serves the catalog as JSON on localhost")
+                .arg(
+                    Arg::with_name("port")
+                        .short("p")
+                        .long("port")
+                        .help("Port to listen on.")
+                        .takes_value(true)
+                        .default_value("8080")
+                )
+                .arg(
+                    Arg::with_name("refresh")
+                        .long("refresh")
+                        .help("Seconds between reloads of the catalog, 0 to never reload.")
+                        .takes_value(true)
+                        .default_value("3600")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("tui")
+                .about("Browses the catalog in a full screen terminal UI")
+        )
+        .subcommand(
+            SubCommand::with_name("completions")
+                .about("Generates shell completions, including brand & model names")
+                .arg(
+                    Arg::with_name("shell")
+                        .help("Shell to generate completions for.")
+                        .required(true)
+                        .possible_values(&["bash", "zsh", "fish"])
+                )
         )
         .subcommand(
             SubCommand::with_name("init")
                 .about("Initialize odin app")
         )
-        .get_matches();
+}
 
-        matches
-}
\ No newline at end of file
+/// Positional arguments identifying a model, shared by `follow`, `unfollow` & `images`
+fn device_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("brand")
+            .help("Brand of the model.")
+            .required(true),
+        Arg::with_name("model")
+            .help("Name of the model.")
+            .required(true),
+        Arg::with_name("version")
+            .help("Hardware version of the model, ex: v2.")
+    ]
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::io::Write;
+use clap::{App, Shell};
+
+use crate::cli;
+
+/// Completes brand names after `-b/--brand` & brand/model names as positional arguments,
+/// asking `odin __complete` for them, else falls back to clap's completions
+///
+/// `@OPTIONS@` is replaced by all options taking a value, see `value_options`, so that
+/// their values aren't taken for positional arguments
+const BASH_DYNAMIC: &str = r#"
+_odin_names() {
+    local file="$1" name
+    local -a options=()
+
+    shift
+    [[ -n "${file}" ]] && options=(-f "${file}")
+
+    COMPREPLY=()
+
+    while IFS= read -r name; do
+        COMPREPLY+=("$(printf '%q' "${name}")")
+    done < <(odin "${options[@]}" __complete "$@" 2>/dev/null)
+}
+
+_odin_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local i cmd="" file=""
+    local -a args=()
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            -f)
+                ((i++))
+                file="${COMP_WORDS[i]}"
+                ;;
+            @OPTIONS@)
+                ((i++))
+                ;;
+            -*)
+                ;;
+            *)
+                if [[ -z "${cmd}" ]]; then
+                    cmd="${COMP_WORDS[i]}"
+                else
+                    args+=("${COMP_WORDS[i]//[\\\"\']/}")
+                fi
+                ;;
+        esac
+    done
+
+    if [[ "${prev}" == "-b" || "${prev}" == "--brand" ]]; then
+        _odin_names "${file}" brands "${cur}"
+        return 0
+    fi
+
+    case "${cmd}" in
+        follow|unfollow|images)
+            if [[ ${#args[@]} -eq 0 && "${cur}" != -* ]]; then
+                _odin_names "${file}" brands "${cur}"
+                return 0
+            elif [[ ${#args[@]} -eq 1 && "${cur}" != -* ]]; then
+                _odin_names "${file}" models "${args[0]}" "${cur}"
+                return 0
+            fi
+            ;;
+    esac
+
+    _odin "$@"
+}
+
+complete -F _odin_dynamic -o bashdefault -o default odin
+"#;
+
+/// Same as `BASH_DYNAMIC`, for zsh, matching names regardless of case
+const ZSH_DYNAMIC: &str = r#"
+_odin_names() {
+    local file="$1"
+    local -a options names
+
+    shift
+    [[ -n "${file}" ]] && options=(-f "${file}")
+
+    names=("${(@f)$(odin "${options[@]}" __complete "$@" 2>/dev/null)}")
+    compadd -M 'm:{a-zA-Z}={A-Za-z}' -a names
+}
+
+_odin_dynamic() {
+    local cur="${words[CURRENT]}" prev="${words[CURRENT-1]}"
+    local i cmd="" file=""
+    local -a args
+
+    for ((i = 2; i < CURRENT; i++)); do
+        case "${words[i]}" in
+            -f)
+                ((i++))
+                file="${words[i]}"
+                ;;
+            @OPTIONS@)
+                ((i++))
+                ;;
+            -*)
+                ;;
+            *)
+                if [[ -z "${cmd}" ]]; then
+                    cmd="${words[i]}"
+                else
+                    args+=("${(Q)words[i]}")
+                fi
+                ;;
+        esac
+    done
+
+    if [[ "${prev}" == "-b" || "${prev}" == "--brand" ]]; then
+        _odin_names "${file}" brands "${cur}"
+        return
+    fi
+
+    case "${cmd}" in
+        follow|unfollow|images)
+            if [[ ${#args} -eq 0 && "${cur}" != -* ]]; then
+                _odin_names "${file}" brands "${cur}"
+                return
+            elif [[ ${#args} -eq 1 && "${cur}" != -* ]]; then
+                _odin_names "${file}" models "${args[1]}" "${cur}"
+                return
+            fi
+            ;;
+    esac
+
+    _odin "$@"
+}
+
+_odin_dynamic "$@"
+"#;
+
+/// Same as `BASH_DYNAMIC`, for fish, on top of clap's completions
+const FISH_DYNAMIC: &str = r#"
+function __odin_args
+    set -l skip 0
+
+    for token in (commandline -opc)[2..-1]
+        if test $skip -eq 1
+            set skip 0
+            continue
+        end
+
+        switch $token
+            case @OPTIONS@
+                set skip 1
+            case '-*'
+            case '*'
+                echo $token
+        end
+    end
+end
+
+function __odin_complete
+    set -l tokens (commandline -opc)
+    set -l index (contains -i -- -f $tokens)
+    set -l options
+
+    if test -n "$index"
+        set options -f $tokens[(math $index + 1)]
+    end
+
+    odin $options __complete $argv 2>/dev/null
+end
+
+complete -c odin -n '__fish_seen_subcommand_from list' -s b -l brand -x -a '(__odin_complete brands)'
+complete -c odin -n '__fish_seen_subcommand_from follow unfollow images; and test (count (__odin_args)) -eq 1' -x -a '(__odin_complete brands)'
+complete -c odin -n '__fish_seen_subcommand_from follow unfollow images; and test (count (__odin_args)) -eq 2' -x -a '(__odin_complete models (__odin_args)[2])'
+"#;
+
+/// Collects all options of `app` & its subcommands taking a value, ex: `-f` & `--format`
+fn value_options(app: &App, options: &mut BTreeSet<String>) {
+    // clap 2 keeps arguments taking a value apart from flags & positional ones
+    for opt in &app.p.opts {
+        options.extend(opt.s.short.map(|short| format!("-{}", short)));
+        options.extend(opt.s.long.map(|long| format!("--{}", long)));
+    }
+
+    for subcommand in &app.p.subcommands {
+        value_options(subcommand, options);
+    }
+}
+
+/// Writes completions for `shell` to `out`, clap's static completions along with
+/// dynamic completion of brand & model names
+pub fn generate<W: Write>(shell: &str, out: &mut W) -> Result<(), Box<dyn Error>> {
+    let shell = shell.parse::<Shell>()?;
+    let mut app = cli::build();
+    let mut script = Vec::new();
+    let mut options = BTreeSet::new();
+
+    value_options(&app, &mut options);
+    app.gen_completions_to("odin", shell, &mut script);
+
+    let script = String::from_utf8(script)?;
+    let options = options.into_iter().collect::<Vec<String>>();
+
+    match shell {
+        Shell::Bash => write!(out, "{}{}", script, BASH_DYNAMIC.replace("@OPTIONS@", &options.join("|")))?,
+        // clap's script ends by calling `_odin`, which the dynamic one takes over
+        Shell::Zsh => write!(out, "{}{}", script.trim_end().trim_end_matches("_odin \"$@\""), ZSH_DYNAMIC.replace("@OPTIONS@", &options.join("|")))?,
+        Shell::Fish => write!(out, "{}{}", script, FISH_DYNAMIC.replace("@OPTIONS@", &options.join(" ")))?,
+        _ => return Err(format!("Unsupported shell: {}", shell).into())
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_options_taking_values() {
+        let mut options = BTreeSet::new();
+
+        value_options(&cli::build(), &mut options);
+
+        for option in ["-f", "--releases", "--hardware", "--max-body-size", "-b", "--group-by", "--template-file", "-n", "--limit"] {
+            assert!(options.contains(option), "Expected {} to take a value", option);
+        }
+
+        for flag in ["-m", "--all", "--count", "--5ghz", "-v", "--quiet"] {
+            assert!(!options.contains(flag), "Expected {} not to take a value", flag);
+        }
+    }
+
+    #[test]
+    fn generates_scripts_skipping_option_values() {
+        for (shell, options) in [("bash", "|--hardware|"), ("zsh", "|--hardware|"), ("fish", " --hardware ")] {
+            let mut script = Vec::new();
+
+            generate(shell, &mut script).unwrap();
+
+            let script = String::from_utf8(script).unwrap();
+            assert!(script.contains("__complete"), "Expected dynamic completions for {}", shell);
+            assert!(script.contains(options), "Expected {} to skip values of --hardware", shell);
+            assert!(!script.contains("@OPTIONS@"), "Expected options to be filled in for {}", shell);
+        }
+
+        assert!(generate("powershell", &mut Vec::new()).is_err());
+    }
+}
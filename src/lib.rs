@@ -0,0 +1,7 @@
+//! # ODIN - OpenWrt Device Information
+//!
+//! Library side of the `odin` CLI, kept separate so the loaders can be benchmarked
+
+#[macro_use]
+pub mod log;
+pub mod app;
@@ -1,21 +1,243 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::RwLock;
 use colored::*;
 
-/// `Unit struct` that provides log related functions
-/// 
-/// Example:
-/// 
-/// Log::print_ok(format!("Hello, {}!", "rust"))
-/// Log::print_error(format!("Shields are at {}%", 10))
-pub struct Log;
+/// Environment variable diagnostics are filtered by, see `Filter`
+pub const ODIN_LOG: &str = "ODIN_LOG";
 
-impl Log {
-    /// Prints `text` with green foreground
-    pub fn print_ok(text: String) {
-        println!("{}", text.green());
+/// # `Level` represents how important a diagnostic is, from most to least
+///
+/// `Error` - something failed, the requested data may be missing or incomplete
+/// `Warn` - something's off, but odin carried on
+/// `Info` - progress & summaries, shown by default
+/// `Debug` - details about what odin is doing, shown with `-v`
+/// `Trace` - everything, shown with `-vv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace
+}
+
+impl Level {
+    /// Returns the level shown with `verbose` `-v`s, or only errors when `quiet`
+    pub fn from_flags(verbose: u64, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Level::Error,
+            (false, 0) => Level::Info,
+            (false, 1) => Level::Debug,
+            (false, _) => Level::Trace
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("Unknown log level: {}", text))
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Error => write!(f, "error"),
+            Level::Warn => write!(f, "warn"),
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+            Level::Trace => write!(f, "trace")
+        }
+    }
+}
+
+/// # `Filter` represents which diagnostics are shown
+///
+/// `level` - most verbose level shown, unless a directive says otherwise
+/// `directives` - levels of modules, the most specific matching one wins
+///
+/// ## Example
+///
+/// "warn,loader=trace" - warnings & errors, everything from the loader & its submodules
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    level: Level,
+    directives: Vec<(String, Level)>
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self { level: Level::Info, directives: Vec::new() }
+    }
+}
+
+impl Filter {
+    /// Sets the level shown outside of modules with a directive
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+
+        self
+    }
+
+    /// Returns whether a diagnostic at `level` from module at `target` is shown
+    ///
+    /// Directives match whole segments of module paths, ex: "loader" matches
+    /// `odin::app::loader` & `odin::app::loader::stream`, but not `odin::app::loaders`
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        let segments = target.split("::")
+            .collect::<Vec<&str>>();
+        let max_level = self.directives
+            .iter()
+            .filter(|(module, _)| {
+                let module = module.split("::").collect::<Vec<&str>>();
+
+                segments.windows(module.len()).any(|window| window == module.as_slice())
+            })
+            .max_by_key(|(module, _)| module.split("::").count())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level);
+
+        level <= max_level
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    /// Parses comma separated directives, each a level or `module=level`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+
+        for directive in text.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => filter.directives.push((module.trim().to_string(), level.parse()?)),
+                None => filter.level = directive.parse()?
+            }
+        }
+
+        Ok(filter)
+    }
+}
+
+/// Filter in effect, see `init`
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+/// Sets up diagnostics from `ODIN_LOG`, with `level` from `-v`/`-q` taking precedence
+/// over its global level when given
+///
+/// An invalid `ODIN_LOG` is reported & ignored
+pub fn init(level: Option<Level>) {
+    let mut filter = match std::env::var(ODIN_LOG) {
+        Ok(text) => text.parse().unwrap_or_else(|err| {
+            eprintln!("{}", format!("Ignoring {}: {}", ODIN_LOG, err).yellow());
+
+            Filter::default()
+        }),
+        Err(_) => Filter::default()
+    };
+
+    if let Some(level) = level {
+        filter = filter.with_level(level);
+    }
+
+    if let Ok(mut current) = FILTER.write() {
+        *current = Some(filter);
+    }
+}
+
+/// Returns whether a diagnostic at `level` from module at `target` is shown,
+/// with the default filter until `init` is called
+pub fn enabled(level: Level, target: &str) -> bool {
+    match FILTER.read() {
+        Ok(filter) => match filter.as_ref() {
+            Some(filter) => filter.enabled(level, target),
+            None => Filter::default().enabled(level, target)
+        },
+        Err(_) => level <= Level::Info
+    }
+}
+
+/// Writes `args` to stderr, colored by `level`, stdout is kept for the requested data
+///
+/// Debug & trace diagnostics are prefixed with their level & module
+pub fn write(level: Level, target: &str, args: fmt::Arguments) {
+    let text = args.to_string();
+
+    match level {
+        Level::Error => eprintln!("{}", text.red()),
+        Level::Warn => eprintln!("{}", text.yellow()),
+        Level::Info => eprintln!("{}", text.green()),
+        Level::Debug | Level::Trace => eprintln!("{}", format!("[{} {}] {}", level, target, text).dimmed())
+    }
+}
+
+/// Writes a diagnostic at given `Level` to stderr, if the filter lets it through
+///
+/// ## Example
+///
+/// log!(Level::Info, "Found {} brand(s)!", 251)
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level, module_path!()) {
+            $crate::log::write($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+macro_rules! error {
+    ($($arg:tt)+) => { log!($crate::log::Level::Error, $($arg)+) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)+) => { log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+macro_rules! info {
+    ($($arg:tt)+) => { log!($crate::log::Level::Info, $($arg)+) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)+) => { log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)+) => { log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_filters() {
+        let filter = "warn, loader=trace, app::serve=error".parse::<Filter>().unwrap();
+
+        assert!(filter.enabled(Level::Warn, "odin::app"));
+        assert!(!filter.enabled(Level::Info, "odin::app"));
+        assert!(filter.enabled(Level::Trace, "odin::app::loader::stream"));
+        assert!(!filter.enabled(Level::Debug, "odin::app::loaders"));
+        assert!(!filter.enabled(Level::Warn, "odin::app::serve"));
+
+        assert!(filter.clone().with_level(Level::Debug).enabled(Level::Debug, "odin::app"));
+        assert!("verbose".parse::<Filter>().is_err());
+        assert_eq!(Filter::default(), "".parse::<Filter>().unwrap());
     }
 
-    /// Prints `text` with red foreground
-    pub fn print_error(text: String) {
-        println!("{}", text.red());
+    #[test]
+    fn maps_flags_to_levels() {
+        assert_eq!(Level::Info, Level::from_flags(0, false));
+        assert_eq!(Level::Debug, Level::from_flags(1, false));
+        assert_eq!(Level::Trace, Level::from_flags(3, false));
+        assert_eq!(Level::Error, Level::from_flags(2, true));
     }
-}
\ No newline at end of file
+}
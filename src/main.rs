@@ -1,31 +1,221 @@
 use std::error::Error;
+use std::io;
+use std::process;
+use clap::ArgMatches;
+
+use odin::app;
+use odin::log::{self, Level};
 
 mod cli;
-mod log;
-mod app;
+mod completions;
+
+/// Returns the level asked for with `-v`/`-q`, `None` when neither was given
+///
+/// Both are global, so they may come after any subcommand
+fn log_level(matches: &ArgMatches) -> Option<Level> {
+    let mut verbose = 0;
+    let mut quiet = false;
+    let mut current = Some(matches);
+
+    while let Some(matches) = current {
+        verbose = verbose.max(matches.occurrences_of("verbose"));
+        quiet |= matches.is_present("quiet");
+        current = matches.subcommand().1;
+    }
+
+    if verbose == 0 && !quiet {
+        return None;
+    }
+
+    Some(Level::from_flags(verbose, quiet))
+}
+
+/// Exits the way an unhandled Ctrl-C would have
+fn exit_interrupted() -> ! {
+    if log::enabled(Level::Error, module_path!()) {
+        log::write(Level::Error, module_path!(), format_args!("Interrupted!"));
+    }
+
+    process::exit(130)
+}
+
+/// Exits with a non-zero status after reporting `err` like any other error, nothing found included
+fn exit_failed(err: &dyn Error) -> ! {
+    if log::enabled(Level::Error, module_path!()) {
+        log::write(Level::Error, module_path!(), format_args!("{}", err));
+    }
+
+    process::exit(1)
+}
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() {
     let matches = cli::parse_args();
+
+    log::init(log_level(&matches));
+
+    // downloads wind down on Ctrl-C, so a page being cached is never cut short, without a
+    // handler Ctrl-C still exits right away
+    let _ = ctrlc::set_handler(|| if !app::loader::interrupt() { exit_interrupted() });
+
+    match run(&matches) {
+        Ok(()) => (),
+        Err(err) if err.is::<app::loader::Interrupted>() => exit_interrupted(),
+        Err(err) => exit_failed(err.as_ref())
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    if let Some(mb) = matches.value_of("max-body-size") {
+        app::loader::set_max_body_mb(mb.parse()?);
+    }
+
     let file = matches.value_of("file");
-    let application = app::App::new(file);
+    let application = app::App::new(file)
+        .with_releases(matches.value_of("releases"))
+        .with_hardware(matches.value_of("hardware"));
 
     if let Some(matches) = matches.subcommand_matches("list") {
+        let format = matches.value_of("format").unwrap_or_default().parse()?;
+        let status = matches.is_present("status");
+        let template = match (matches.value_of("template"), matches.value_of("template-file")) {
+            (Some(template), _) => Some(app::Template::new(template)?),
+            (None, Some(file)) => Some(app::Template::load(file)?),
+            (None, None) => None
+        };
+        let minimum = app::Resources {
+            flash_mb: matches.value_of("min-flash").map(str::parse).transpose()?,
+            ram_mb: matches.value_of("min-ram").map(str::parse).transpose()?
+        };
+
         if matches.is_present("models") {
             if matches.is_present("all") {
-                application.list_models_for_all()?;
+                application.list_models_for_all(format, status, minimum, template.as_ref())?;
             }
             else if let Some(brand) = matches.value_of("brand") {
-                application.list_models_for(brand)?;
+                application.list_models_for(brand, format, status, minimum, template.as_ref())?;
             }
             else {
                 println!("{}", matches.usage());
             }
         }
         else {
-            application.list_brands()?;
+            application.list_brands(format, template.as_ref())?;
+        }
+    }
+    else if let Some(matches) = matches.subcommand_matches("releases") {
+        application.releases(matches.value_of("format").unwrap_or_default().parse()?)?;
+    }
+    else if matches.subcommand_matches("schema").is_some() {
+        application.schema()?;
+    }
+    else if matches.subcommand_matches("lint").is_some() {
+        application.lint()?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("check-links") {
+        application.check_links(
+            matches.value_of("mirror"),
+            matches.value_of("jobs").unwrap_or_default().parse()?,
+            matches.value_of("timeout").unwrap_or_default().parse()?
+        )?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("fleet") {
+        if let Some(matches) = matches.subcommand_matches("report") {
+            let inventory = matches.value_of("inventory").unwrap_or_default();
+            let format = matches.value_of("format").unwrap_or_default().parse()?;
+
+            application.fleet_report(inventory, format)?;
+        }
+        else {
+            println!("{}", matches.usage());
+        }
+    }
+    else if let Some(matches) = matches.subcommand_matches("compare") {
+        application.compare(
+            &matches.values_of("models").map(|models| models.collect::<Vec<&str>>()).unwrap_or_default(),
+            matches.value_of("format").unwrap_or_default().parse()?
+        )?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("recommend") {
+        let requirements = app::Requirements {
+            release: matches.value_of("release").map(String::from),
+            min_flash_mb: matches.value_of("min-flash").map(str::parse).transpose()?,
+            min_ram_mb: matches.value_of("min-ram").map(str::parse).transpose()?,
+            wifi_5ghz: matches.is_present("wifi-5ghz"),
+            lan_ports: matches.value_of("lan-ports").map(str::parse).transpose()?
+        };
+
+        application.recommend(
+            matches.value_of("requirements"),
+            requirements,
+            matches.value_of("limit").unwrap_or_default().parse()?,
+            matches.value_of("format").unwrap_or_default().parse()?
+        )?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("query") {
+        application.query(
+            matches.value_of("expression"),
+            matches.value_of("group-by"),
+            matches.is_present("count"),
+            matches.value_of("format").unwrap_or_default().parse()?
+        )?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("export") {
+        if let Some(matches) = matches.subcommand_matches("sqlite") {
+            application.export_sqlite(matches.value_of("path").unwrap_or_default())?;
+        }
+        else {
+            println!("{}", matches.usage());
         }
     }
-    else if let Some(_) = matches.subcommand_matches("init") {
+    else if let Some(matches) = matches.subcommand_matches("follow") {
+        application.follow(
+            matches.value_of("brand").unwrap_or_default(),
+            matches.value_of("model").unwrap_or_default(),
+            matches.value_of("version")
+        )?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("unfollow") {
+        application.unfollow(
+            matches.value_of("brand").unwrap_or_default(),
+            matches.value_of("model").unwrap_or_default(),
+            matches.value_of("version")
+        )?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("status") {
+        application.status(matches.value_of("exec"))?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("identify") {
+        application.identify(matches.value_of("board").unwrap_or_default())?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("images") {
+        application.images(
+            matches.value_of("brand").unwrap_or_default(),
+            matches.value_of("model").unwrap_or_default(),
+            matches.value_of("version"),
+            matches.values_of("profiles").map(|profiles| profiles.collect()),
+            matches.value_of("format").unwrap_or_default().parse()?
+        )?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("serve") {
+        application.serve(
+            matches.value_of("port").unwrap_or_default().parse()?,
+            matches.value_of("refresh").unwrap_or_default().parse()?
+        )?;
+    }
+    else if matches.subcommand_matches("tui").is_some() {
+        application.tui()?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("completions") {
+        completions::generate(matches.value_of("shell").unwrap_or_default(), &mut io::stdout())?;
+    }
+    else if let Some(matches) = matches.subcommand_matches("__complete") {
+        let values = matches.values_of("values")
+            .map(|values| values.collect::<Vec<&str>>())
+            .unwrap_or_default();
+
+        application.complete(matches.value_of("kind").unwrap_or_default(), &values)?;
+    }
+    else if matches.subcommand_matches("init").is_some() {
         application.init();
     }
     else {
@@ -0,0 +1,17 @@
+//! Ctrl-C handling of downloads, apart from other tests as it's process wide
+
+use odin::app::loader::{self, Interrupted, Interruptible};
+
+#[test]
+fn notes_ctrl_c_while_interruptible() {
+    assert!(!loader::interrupt(), "Expected Ctrl-C to exit without interruptible work");
+
+    let interruptible = Interruptible::begin();
+    assert!(interruptible.check().is_ok());
+
+    assert!(loader::interrupt());
+    assert!(interruptible.check().unwrap_err().is::<Interrupted>());
+
+    drop(interruptible);
+    assert!(Interruptible::begin().check().is_ok(), "Expected Ctrl-C to be forgotten once work is done");
+}